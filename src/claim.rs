@@ -0,0 +1,541 @@
+use crate::kan::DeclareKan;
+use crate::meld::{calculate_meld_animations, Meld, MeldKind};
+use crate::player::{
+    calculate_hand_animation, DiscardTile, Player, PlayerIdent, PlayerType, Players,
+};
+use crate::riichi::DepositRiichiStick;
+use crate::round::{self, DeclareWin, Round};
+use crate::rules::{KanDora, MultipleRon, Rules};
+use crate::ryuukyoku::{self, AbortiveDraw, AbortiveDrawKind, ExhaustiveDraw};
+use crate::tiles::{Honor, Tile, Wind};
+use crate::turn::TurnPhase;
+use crate::wall::{Doras, RevealDora, TileEntity, Wall};
+use bevy::prelude::*;
+
+const CLAIM_WINDOW_SECONDS: f32 = 0.5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClaimKind {
+    Chi,
+    Pon,
+    Kan,
+    Ron,
+}
+
+impl ClaimKind {
+    fn priority(self) -> u8 {
+        match self {
+            Self::Chi => 0,
+            Self::Pon | Self::Kan => 1,
+            Self::Ron => 2,
+        }
+    }
+
+    fn key(self) -> KeyCode {
+        match self {
+            Self::Chi => KeyCode::C,
+            Self::Pon => KeyCode::P,
+            Self::Kan => KeyCode::K,
+            Self::Ron => KeyCode::R,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Claim {
+    seat: Wind,
+    kind: ClaimKind,
+    /// Indices of the tiles in the hand of the claiming player which form the meld.
+    tiles: Vec<usize>,
+}
+
+pub struct ClaimWindow {
+    timer: Timer,
+    human_timeout: f32,
+    opened: bool,
+    discarder: Option<Wind>,
+    options: Vec<Claim>,
+    /// Which of several chi options the human calls with C.
+    chi_choice: usize,
+    claims: Vec<Claim>,
+}
+
+impl ClaimWindow {
+    pub fn new(human_timeout: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(CLAIM_WINDOW_SECONDS, false),
+            human_timeout,
+            opened: false,
+            discarder: None,
+            options: Vec::new(),
+            chi_choice: 0,
+            claims: Vec::new(),
+        }
+    }
+
+    fn open(&mut self, discarder: Wind, options: Vec<Claim>, claims: Vec<Claim>) {
+        let duration = if options.is_empty() {
+            CLAIM_WINDOW_SECONDS
+        } else {
+            self.human_timeout
+        };
+
+        self.timer = Timer::from_seconds(duration, false);
+        self.opened = true;
+        self.discarder = Some(discarder);
+        self.options = options;
+        self.chi_choice = 0;
+        self.claims = claims;
+    }
+
+    fn chi_options(&self) -> Vec<&Claim> {
+        self.options
+            .iter()
+            .filter(|claim| claim.kind == ClaimKind::Chi)
+            .collect()
+    }
+
+    /// The option of the human for the pressed key, out of several chi the selected one.
+    fn chosen_option(&self, keyboard: &Input<KeyCode>) -> Option<Claim> {
+        let kind = [
+            ClaimKind::Ron,
+            ClaimKind::Kan,
+            ClaimKind::Pon,
+            ClaimKind::Chi,
+        ]
+        .iter()
+        .copied()
+        .find(|kind| keyboard.just_pressed(kind.key()))?;
+
+        if kind == ClaimKind::Chi {
+            return self
+                .chi_options()
+                .get(self.chi_choice)
+                .map(|&claim| claim.clone());
+        }
+
+        self.options
+            .iter()
+            .find(|claim| claim.kind == kind)
+            .cloned()
+    }
+
+    /// Picks the claim with the highest priority. Between equal claims the player who would
+    /// play first after the discarder wins.
+    fn resolve(&self) -> Option<&Claim> {
+        let discarder = self.discarder? as usize;
+
+        self.claims.iter().max_by_key(|claim| {
+            let distance = (4 + claim.seat as usize - discarder) % 4;
+            (claim.kind.priority(), 4 - distance)
+        })
+    }
+
+    /// Seats of all players claiming ron in turn order after the discarder.
+    fn rons(&self) -> Vec<Wind> {
+        let discarder = match self.discarder {
+            Some(discarder) => discarder as usize,
+            None => return Vec::new(),
+        };
+
+        let mut seats = self
+            .claims
+            .iter()
+            .filter(|claim| claim.kind == ClaimKind::Ron)
+            .map(|claim| claim.seat)
+            .collect::<Vec<_>>();
+        seats.sort_by_key(|&seat| (4 + seat as usize - discarder) % 4);
+        seats
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallTile {
+    seat: Wind,
+    discarder: Wind,
+    kind: MeldKind,
+    tiles: Vec<usize>,
+}
+
+fn possible_claims(
+    player: &Player,
+    players: &Players,
+    discarder: Wind,
+    discard: TileEntity,
+    round: &Round,
+    doras: &Doras,
+    wall: &Wall,
+    rules: &Rules,
+) -> Vec<Claim> {
+    let mut claims = Vec::new();
+
+    let claim = |kind, tiles| Claim {
+        seat: player.seat,
+        kind,
+        tiles,
+    };
+
+    let tile = discard.tile;
+
+    if !player.is_furiten()
+        && round::evaluate_win(player, players, wall, round, doras, rules, Some(discard)).is_some()
+    {
+        claims.push(claim(ClaimKind::Ron, Vec::new()));
+    }
+
+    // The hand is locked after riichi, the last discard and a tile added to a kan can only be
+    // won on.
+    let is_last_discard = wall.is_exhausted();
+    let is_added_kan_tile = players
+        .player_by_ident(PlayerIdent::Seat(discarder))
+        .added_kan_tile()
+        .is_some();
+    if player.riichi.is_some() || is_last_discard || is_added_kan_tile {
+        return claims;
+    }
+
+    let find = |tile: Tile, amount: usize| {
+        let indices = player
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile_entity)| tile_entity.tile == tile)
+            .map(|(index, _)| index)
+            .take(amount)
+            .collect::<Vec<_>>();
+
+        if indices.len() == amount {
+            Some(indices)
+        } else {
+            None
+        }
+    };
+
+    if let Some(indices) = find(tile, 2) {
+        claims.push(claim(ClaimKind::Pon, indices));
+    }
+
+    let can_kan = players.can_declare_kan() && !is_last_discard;
+    if let (true, Some(indices)) = (can_kan, find(tile, 3)) {
+        claims.push(claim(ClaimKind::Kan, indices));
+    }
+
+    let is_next = (discarder as usize + 1) % 4 == player.seat as usize;
+
+    if is_next {
+        // Every run containing the discard starts with the discard or one of the hand tiles.
+        let mut lows = player.hand_tiles();
+        lows.push(tile);
+        lows.sort_unstable();
+        lows.dedup();
+
+        for low in lows {
+            let run = std::iter::successors(Some(low), |tile| tile.successor())
+                .take(3)
+                .collect::<Vec<_>>();
+
+            if run.len() < 3 || !run.contains(&tile) {
+                continue;
+            }
+
+            let indices = run
+                .iter()
+                .filter(|&&other| other != tile)
+                .map(|&other| find(other, 1))
+                .collect::<Option<Vec<_>>>();
+
+            if let Some(indices) = indices {
+                claims.push(claim(ClaimKind::Chi, indices.concat()));
+            }
+        }
+    }
+
+    claims
+}
+
+/// The AI takes every win and calls triplets of value honors, which guarantee a yaku.
+fn choose_ai_claim(options: Vec<Claim>, player: &Player, round: &Round) -> Option<Claim> {
+    options.into_iter().find(|claim| match claim.kind {
+        ClaimKind::Ron => true,
+        ClaimKind::Pon => match player.tiles[claim.tiles[0]].tile {
+            Tile::Honor(Honor::Dragon(_)) => true,
+            Tile::Honor(Honor::Wind(wind)) => wind == player.wind || wind == round.wind,
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+pub fn open_claim_window_system(mut claim_window: ResMut<ClaimWindow>) {
+    claim_window.opened = false;
+}
+
+pub fn claim_window_system(
+    commands: &mut Commands,
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    round: Res<Round>,
+    doras: Res<Doras>,
+    wall: Res<Wall>,
+    rules: Res<Rules>,
+    mut state: ResMut<State<TurnPhase>>,
+    mut players: ResMut<Players>,
+    mut claim_window: ResMut<ClaimWindow>,
+    discard_events: Query<Entity, With<DiscardTile>>,
+    kan_events: Query<Entity, With<DeclareKan>>,
+) {
+    if discard_events.iter().next().is_some() || kan_events.iter().next().is_some() {
+        return;
+    }
+
+    if !claim_window.opened {
+        let discarder = players.current_player();
+        let discard = match discarder.claimable_tile() {
+            Some(discard) => discard,
+            None => return,
+        };
+
+        let mut options = Vec::new();
+        let mut claims = Vec::new();
+
+        for player in players
+            .iter()
+            .filter(|player| player.seat != discarder.seat)
+        {
+            let player_options = possible_claims(
+                player,
+                &players,
+                discarder.seat,
+                discard,
+                &round,
+                &doras,
+                &wall,
+                &rules,
+            );
+
+            match player.r#type {
+                PlayerType::Human => options = player_options,
+                PlayerType::Ai => claims.extend(choose_ai_claim(player_options, player, &round)),
+            }
+        }
+
+        claim_window.open(discarder.seat, options, claims);
+
+        if claim_window.chi_options().len() > 1 {
+            info!("Several chi are possible, pick one with Left and Right.");
+        }
+    }
+
+    let mut human_done = claim_window.options.is_empty();
+
+    // Left and right pick between several chi before calling with C.
+    let chi_count = claim_window.chi_options().len();
+    if chi_count > 1 {
+        let step = if keyboard.just_pressed(KeyCode::Right) {
+            1
+        } else if keyboard.just_pressed(KeyCode::Left) {
+            chi_count - 1
+        } else {
+            0
+        };
+
+        if step > 0 {
+            claim_window.chi_choice = (claim_window.chi_choice + step) % chi_count;
+
+            let claim = claim_window.chi_options()[claim_window.chi_choice];
+            let player = players.player_by_ident(PlayerIdent::Seat(claim.seat));
+            let tiles = claim
+                .tiles
+                .iter()
+                .map(|&index| player.tiles[index].tile.to_string())
+                .collect::<String>();
+            info!("Chi with {} selected.", tiles);
+        }
+    }
+
+    if let Some(claim) = claim_window.chosen_option(&keyboard) {
+        claim_window.claims.push(claim);
+        human_done = true;
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape) || keyboard.just_pressed(KeyCode::Space) {
+        human_done = true;
+    }
+
+    let timed_out = claim_window.timer.tick(time.delta_seconds()).finished();
+
+    if !(timed_out || (human_done && !claim_window.options.is_empty())) {
+        return;
+    }
+
+    claim_window.options.clear();
+    claim_window.opened = false;
+
+    let discarder = players.current_player();
+    let last_discard = discarder.river.last().copied();
+    let added_kan_tile = discarder.added_kan_tile();
+    let passed_tile = discarder.claimable_tile();
+    let discarder = discarder.seat;
+
+    let resolved = claim_window.resolve().cloned();
+
+    let is_ron = matches!(
+        resolved,
+        Some(Claim {
+            kind: ClaimKind::Ron,
+            ..
+        })
+    );
+    if let (Some(passed_tile), false) = (passed_tile, is_ron) {
+        // The riichi is only valid if the declaration tile does not deal in.
+        let is_riichi_discard = last_discard.map_or(false, |discard| discard.riichi);
+        if is_riichi_discard && added_kan_tile.is_none() {
+            commands.spawn((DepositRiichiStick::new(discarder),));
+        }
+
+        // Everyone letting a winning tile pass is furiten until their next discard.
+        for seat in Wind::iter().filter(|&seat| seat != discarder) {
+            let player = players.player_by_ident_mut(PlayerIdent::Seat(seat));
+
+            if player.winning_tiles().contains(&passed_tile.tile) {
+                player.miss_win();
+            }
+        }
+    }
+
+    let rons = claim_window.rons();
+    let is_triple_ron = rons.len() == 3 && rules.multiple_ron != MultipleRon::Triple;
+
+    let abortive_draw = [
+        (AbortiveDrawKind::TripleRon, is_triple_ron),
+        (
+            AbortiveDrawKind::FourRiichi,
+            !is_ron && ryuukyoku::is_four_riichi(&players),
+        ),
+        (
+            AbortiveDrawKind::FourWinds,
+            !is_ron && ryuukyoku::is_four_winds(&players),
+        ),
+        (
+            AbortiveDrawKind::FourKans,
+            resolved.is_none() && added_kan_tile.is_none() && players.has_four_kans_abort(),
+        ),
+    ]
+    .iter()
+    .find(|&&(kind, applies)| applies && rules.abortive_draws.allows(kind))
+    .map(|&(kind, _)| kind);
+
+    if let Some(kind) = abortive_draw {
+        commands.spawn((AbortiveDraw::new(kind),));
+        state.set_next(TurnPhase::HandEnd).unwrap();
+        return;
+    }
+
+    match resolved {
+        Some(Claim {
+            kind: ClaimKind::Ron,
+            ..
+        }) => {
+            for &seat in rons.iter().take(rules.multiple_ron.max_winners()) {
+                commands.spawn((DeclareWin::ron(seat, discarder),));
+            }
+            state.set_next(TurnPhase::HandEnd).unwrap();
+        }
+        Some(Claim { seat, kind, tiles }) => {
+            let kind = match kind {
+                ClaimKind::Chi => MeldKind::Chi,
+                ClaimKind::Pon => MeldKind::Pon,
+                _ => MeldKind::Kan,
+            };
+
+            commands.spawn((CallTile {
+                seat,
+                discarder,
+                kind,
+                tiles,
+            },));
+            players.set_current(PlayerIdent::Seat(seat));
+            state.set_next(TurnPhase::Call).unwrap();
+        }
+        // Nobody robbed the kan, so the replacement tile is drawn.
+        None if added_kan_tile.is_some() => {
+            state.set_next(TurnPhase::Call).unwrap();
+        }
+        None if wall.is_exhausted() => {
+            commands.spawn((ExhaustiveDraw,));
+            state.set_next(TurnPhase::HandEnd).unwrap();
+        }
+        None => {
+            state.set_next(TurnPhase::NextPlayer).unwrap();
+        }
+    }
+}
+
+pub fn call_tile_system(
+    commands: &mut Commands,
+    mut players: ResMut<Players>,
+    mut doras: ResMut<Doras>,
+    rules: Res<Rules>,
+    events: Query<(Entity, &CallTile)>,
+    transform_query: Query<&Transform>,
+) {
+    for (event, call) in events.iter() {
+        let called = players
+            .player_by_ident_mut(PlayerIdent::Seat(call.discarder))
+            .river
+            .call_last(call.seat);
+
+        players.break_ippatsu();
+        let player = players.player_by_ident_mut(PlayerIdent::Seat(call.seat));
+
+        let result = match called {
+            Some(called) => player.call(call.kind, call.discarder, called, &call.tiles),
+            None => Err(format!(
+                "Player sitting {:?} has no discard to call!",
+                call.discarder
+            )),
+        };
+
+        match result {
+            Ok(_) => {
+                if let Some(called) = player.melds.last().and_then(Meld::called_tile) {
+                    info!(
+                        "Player sitting {:?} called {:?} on {} from {:?}.",
+                        call.seat, call.kind, called.tile, call.discarder
+                    );
+                }
+
+                for (entity, animation) in
+                    calculate_meld_animations(&player.melds, player.seat, &transform_query)
+                {
+                    commands.insert_one(entity, animation);
+                }
+
+                for (index, tile_entity) in player.tiles.iter().enumerate() {
+                    if let Ok(transform) = transform_query.get(tile_entity.entity) {
+                        let animation = calculate_hand_animation(
+                            player.hand_position(index),
+                            player.seat,
+                            *transform,
+                            false,
+                        );
+                        commands.insert_one(tile_entity.entity, animation);
+                    }
+                }
+
+                if call.kind == MeldKind::Kan {
+                    let immediately = rules.kan_dora == KanDora::Immediate;
+
+                    for _ in 0..doras.reveal_for_kan(immediately) {
+                        commands.spawn((RevealDora,));
+                    }
+                }
+            }
+            Err(msg) => {
+                error!("{}", msg);
+            }
+        }
+
+        commands.despawn(event);
+    }
+}
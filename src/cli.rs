@@ -0,0 +1,54 @@
+use crate::rules::preset::Preset;
+use crate::rules::RULES_FILE;
+
+#[derive(Debug)]
+pub struct Options {
+    pub rules_file: String,
+    pub preset: Option<Preset>,
+    pub seed: Option<u64>,
+    /// Seed and hash of a wall to check instead of starting the game.
+    pub verify: Option<(String, String)>,
+    /// Logs tiles in ASCII instead of their Unicode glyphs.
+    pub ascii: bool,
+}
+
+impl Options {
+    /// Parses `--rules <file>`, `--preset <name>`, `--seed <number>`,
+    /// `--verify <seed> <hash>` and `--ascii`.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            rules_file: RULES_FILE.to_string(),
+            preset: None,
+            seed: None,
+            verify: None,
+            ascii: false,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--rules" => options.rules_file = value_of(&arg, args.next())?,
+                "--preset" => options.preset = Some(value_of(&arg, args.next())?.parse()?),
+                "--seed" => {
+                    let value = value_of(&arg, args.next())?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Seed {} is not a number!", value))?;
+                    options.seed = Some(seed);
+                }
+                "--verify" => {
+                    let seed = value_of(&arg, args.next())?;
+                    let hash = value_of(&arg, args.next())?;
+                    options.verify = Some((seed, hash));
+                }
+                "--ascii" => options.ascii = true,
+                _ => return Err(format!("Unknown argument {}!", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn value_of(arg: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("Missing value for {}!", arg))
+}
@@ -0,0 +1,132 @@
+//! Every wall is shuffled from its own seed. The hash of the wall order is published before the
+//! hand and the seed after it, so anyone can check with `--verify <seed> <hash>` that the wall
+//! was not changed during the hand. The wall seeds are drawn from the `GameRng`, whose seed is
+//! only logged at the end of the match.
+
+use crate::rules::Rules;
+use crate::tiles::notation::{self, Notated};
+use crate::tiles::Tile;
+use crate::wall::SplitWall;
+use bevy::prelude::*;
+use rand::prelude::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use sha2::{Digest, Sha256};
+
+pub type WallSeed = [u8; 32];
+
+pub struct ShuffledWall {
+    /// Tiles and whether they are red fives, in the order they are taken from the wall.
+    pub tiles: Vec<(Tile, bool)>,
+    pub dice: usize,
+}
+
+impl ShuffledWall {
+    pub fn new(seed: WallSeed, rules: &Rules) -> Self {
+        let mut rng = ChaCha8Rng::from_seed(seed);
+
+        let mut tiles = Tile::new_set_with_red_fives(rules.red_fives);
+        tiles.shuffle(&mut rng);
+        let dice = rng.gen_range(2..=12);
+
+        Self { tiles, dice }
+    }
+
+    /// SHA-256 of the id and red five flag of every tile in wall order.
+    pub fn hash(&self) -> String {
+        let bytes = self
+            .tiles
+            .iter()
+            .flat_map(|&(tile, red)| vec![tile.id(), red as u8])
+            .collect::<Vec<_>>();
+
+        to_hex(&Sha256::digest(&bytes))
+    }
+}
+
+/// Seed and hash of the current wall, the seed stays secret until the hand is over.
+pub struct WallCommitment {
+    seed: WallSeed,
+    pub hash: String,
+}
+
+impl WallCommitment {
+    pub fn new(seed: WallSeed, wall: &ShuffledWall) -> Self {
+        let hash = wall.hash();
+        info!("Hash of the wall is {}.", hash);
+
+        Self { seed, hash }
+    }
+}
+
+pub fn reveal_wall_seed_system(commitment: Res<WallCommitment>) {
+    info!(
+        "Seed of the wall was {}, check it with --verify {} {}",
+        to_hex(&commitment.seed),
+        to_hex(&commitment.seed),
+        commitment.hash
+    );
+}
+
+/// Shuffles the wall of the seed again and compares it with the published hash. The red fives
+/// of the chosen rules change the wall, so they are reported together with the wall as the dead
+/// wall is split off by `build_wall_system`.
+pub fn verify(seed: &str, hash: &str, rules: &Rules) -> Result<String, String> {
+    let seed = from_hex(seed)?;
+    let mut wall_seed = WallSeed::default();
+
+    if seed.len() != wall_seed.len() {
+        return Err(format!("The seed must be {} bytes long!", wall_seed.len()));
+    }
+
+    wall_seed.copy_from_slice(&seed);
+
+    let wall = ShuffledWall::new(wall_seed, rules);
+    let wall_hash = wall.hash();
+
+    if !wall_hash.eq_ignore_ascii_case(hash) {
+        return Err(format!(
+            "The wall of the seed has the hash {} instead of {} with the red fives {:?}!",
+            wall_hash, hash, rules.red_fives
+        ));
+    }
+
+    let format = |tiles: Vec<&(Tile, bool)>| {
+        let tiles = tiles
+            .into_iter()
+            .map(|&(tile, red)| Notated { tile, red })
+            .collect::<Vec<_>>();
+        notation::format(&tiles)
+    };
+
+    let dice = wall.dice;
+    let split = SplitWall::new(wall.tiles);
+
+    Ok(format!(
+        "The wall matches the hash.\nRed fives: {:?}\nDice: {}\nDora indicators: {}\nUra dora indicators: {}\nReplacement tiles: {}\nLiving wall: {}",
+        rules.red_fives,
+        dice,
+        format(split.indicators().collect()),
+        format(split.ura_indicators().collect()),
+        format(split.kan_tiles.iter().collect()),
+        format(split.living_tiles.iter().collect())
+    ))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return Err(format!("{} is not hexadecimal!", hex));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&hex[index..index + 2], 16)
+                .map_err(|_| format!("{} is not hexadecimal!", hex))
+        })
+        .collect()
+}
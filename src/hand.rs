@@ -0,0 +1,285 @@
+use crate::tiles::counts::TileCounts;
+use crate::tiles::{Tile, TERMINALS_AND_HONORS, TILE_KINDS};
+
+pub const TILES_IN_HAND: usize = 13;
+const TILES_IN_COMPLETE_HAND: usize = TILES_IN_HAND + 1;
+const SETS_IN_COMPLETE_HAND: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SetKind {
+    Sequence,
+    Triplet,
+    Quad,
+}
+
+/// A sequence is identified by its lowest tile, triplets and quads by their only tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Set {
+    pub kind: SetKind,
+    pub tile: Tile,
+    pub open: bool,
+}
+
+impl Set {
+    pub fn new(kind: SetKind, tile: Tile, open: bool) -> Self {
+        Self { kind, tile, open }
+    }
+
+    pub fn tiles(&self) -> Vec<Tile> {
+        match self.kind {
+            SetKind::Sequence => std::iter::successors(Some(self.tile), |tile| tile.successor())
+                .take(3)
+                .collect(),
+            SetKind::Triplet => vec![self.tile; 3],
+            SetKind::Quad => vec![self.tile; 4],
+        }
+    }
+
+    pub fn contains(&self, tile: Tile) -> bool {
+        self.tiles().contains(&tile)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decomposition {
+    Standard { pair: Tile, sets: Vec<Set> },
+    SevenPairs { pairs: Vec<Tile> },
+    ThirteenOrphans { pair: Tile },
+}
+
+/// Lists every way the concealed tiles together with the already declared melds form a
+/// complete hand. The concealed tiles include the winning tile.
+pub fn decompose(concealed: &[Tile], melds: &[Set]) -> Vec<Decomposition> {
+    // Bonus tiles never take part in a hand.
+    let mut counts = TileCounts::new();
+    if concealed.iter().any(|&tile| counts.add(tile).is_err()) {
+        return Vec::new();
+    }
+
+    if concealed.len() + melds.len() * 3 != TILES_IN_COMPLETE_HAND {
+        return Vec::new();
+    }
+
+    let mut decompositions = decompose_standard(*counts, melds);
+
+    if melds.is_empty() {
+        decompositions.extend(decompose_seven_pairs(&counts));
+        decompositions.extend(decompose_thirteen_orphans(&counts));
+    }
+
+    decompositions
+}
+
+fn decompose_standard(mut counts: [u8; TILE_KINDS], melds: &[Set]) -> Vec<Decomposition> {
+    let mut decompositions = Vec::new();
+
+    for pair in 0..TILE_KINDS {
+        if counts[pair] < 2 {
+            continue;
+        }
+
+        counts[pair] -= 2;
+
+        let mut sets = melds.to_vec();
+        let mut found = Vec::new();
+        find_sets(&mut counts, 0, None, &mut sets, &mut found);
+
+        decompositions.extend(found.into_iter().map(|sets| Decomposition::Standard {
+            pair: Tile::from_index(pair).unwrap(),
+            sets,
+        }));
+
+        counts[pair] += 2;
+    }
+
+    decompositions
+}
+
+/// Extracts sets starting at the lowest remaining tile. Once a sequence was taken from a tile
+/// no triplet is taken from it anymore, otherwise the same sets are found twice.
+fn find_sets(
+    counts: &mut [u8; TILE_KINDS],
+    start: usize,
+    without_triplet: Option<usize>,
+    sets: &mut Vec<Set>,
+    found: &mut Vec<Vec<Set>>,
+) {
+    let index = match (start..TILE_KINDS).find(|&index| counts[index] > 0) {
+        Some(index) => index,
+        None => {
+            if sets.len() == SETS_IN_COMPLETE_HAND {
+                found.push(sets.clone());
+            }
+            return;
+        }
+    };
+
+    let tile = Tile::from_index(index).unwrap();
+
+    if counts[index] >= 3 && without_triplet != Some(index) {
+        counts[index] -= 3;
+        sets.push(Set::new(SetKind::Triplet, tile, false));
+        find_sets(counts, index, None, sets, found);
+        sets.pop();
+        counts[index] += 3;
+    }
+
+    if starts_sequence(index) && counts[index + 1] > 0 && counts[index + 2] > 0 {
+        counts[index] -= 1;
+        counts[index + 1] -= 1;
+        counts[index + 2] -= 1;
+        sets.push(Set::new(SetKind::Sequence, tile, false));
+        find_sets(counts, index, Some(index), sets, found);
+        sets.pop();
+        counts[index] += 1;
+        counts[index + 1] += 1;
+        counts[index + 2] += 1;
+    }
+}
+
+fn starts_sequence(index: usize) -> bool {
+    Tile::from_index(index)
+        .and_then(Tile::successor)
+        .and_then(Tile::successor)
+        .is_some()
+}
+
+fn decompose_seven_pairs(counts: &[u8; TILE_KINDS]) -> Option<Decomposition> {
+    let pairs = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count == 2)
+        .filter_map(|(index, _)| Tile::from_index(index))
+        .collect::<Vec<_>>();
+
+    if pairs.len() == 7 {
+        Some(Decomposition::SevenPairs { pairs })
+    } else {
+        None
+    }
+}
+
+fn decompose_thirteen_orphans(counts: &[u8; TILE_KINDS]) -> Option<Decomposition> {
+    if TERMINALS_AND_HONORS.iter().any(|&index| counts[index] == 0) {
+        return None;
+    }
+
+    let total = counts.iter().map(|&count| count as usize).sum::<usize>();
+    let orphans = TERMINALS_AND_HONORS
+        .iter()
+        .map(|&index| counts[index] as usize)
+        .sum::<usize>();
+
+    if total != orphans {
+        return None;
+    }
+
+    TERMINALS_AND_HONORS
+        .iter()
+        .find(|&&index| counts[index] == 2)
+        .and_then(|&index| Tile::from_index(index))
+        .map(|pair| Decomposition::ThirteenOrphans { pair })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiles::notation::{tile, tiles};
+    use crate::tiles::Dragon;
+
+    #[test]
+    fn finds_every_standard_decomposition() {
+        let decompositions = decompose(&tiles("111222333m456p77z"), &[]);
+
+        assert_eq!(decompositions.len(), 2);
+        assert!(decompositions.iter().all(|decomposition| matches!(
+            decomposition,
+            Decomposition::Standard { pair, .. } if *pair == Tile::from(Dragon::Red)
+        )));
+
+        let kinds = decompositions
+            .iter()
+            .map(|decomposition| match decomposition {
+                Decomposition::Standard { sets, .. } => sets
+                    .iter()
+                    .filter(|set| set.kind == SetKind::Triplet)
+                    .count(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        assert!(kinds.contains(&3));
+        assert!(kinds.contains(&0));
+    }
+
+    #[test]
+    fn finds_seven_pairs() {
+        let decompositions = decompose(&tiles("1122m3344p5566s77z"), &[]);
+
+        assert_eq!(decompositions.len(), 1);
+        assert!(matches!(
+            &decompositions[0],
+            Decomposition::SevenPairs { pairs } if pairs.len() == 7
+        ));
+    }
+
+    #[test]
+    fn finds_seven_pairs_next_to_a_standard_hand() {
+        let decompositions = decompose(&tiles("112233m445566p77z"), &[]);
+
+        assert!(decompositions
+            .iter()
+            .any(|decomposition| matches!(decomposition, Decomposition::SevenPairs { .. })));
+        assert!(decompositions
+            .iter()
+            .any(|decomposition| matches!(decomposition, Decomposition::Standard { .. })));
+    }
+
+    #[test]
+    fn four_identical_tiles_are_not_two_pairs() {
+        assert!(decompose(&tiles("1111m2233p4455s66z"), &[]).is_empty());
+    }
+
+    #[test]
+    fn finds_thirteen_orphans() {
+        let decompositions = decompose(&tiles("19m19p19s12345677z"), &[]);
+
+        assert_eq!(
+            decompositions,
+            vec![Decomposition::ThirteenOrphans { pair: tile("7z") }]
+        );
+    }
+
+    #[test]
+    fn counts_open_melds() {
+        let melds = [Set::new(SetKind::Triplet, tile("5z"), true)];
+        let decompositions = decompose(&tiles("123m456p789s11z"), &melds);
+
+        assert_eq!(decompositions.len(), 1);
+        match &decompositions[0] {
+            Decomposition::Standard { pair, sets } => {
+                assert_eq!(*pair, tile("1z"));
+                assert_eq!(sets.len(), 4);
+                assert!(sets.contains(&melds[0]));
+            }
+            decomposition => panic!("Unexpected {:?}", decomposition),
+        }
+    }
+
+    #[test]
+    fn counts_kans_as_sets() {
+        let melds = [
+            Set::new(SetKind::Quad, tile("1m"), false),
+            Set::new(SetKind::Quad, tile("9p"), true),
+        ];
+
+        assert_eq!(decompose(&tiles("234m789s11z"), &melds).len(), 1);
+        assert!(decompose(&tiles("234m789s1z"), &melds).is_empty());
+    }
+
+    #[test]
+    fn rejects_incomplete_hands() {
+        assert!(decompose(&tiles("123m456p789s1234z"), &[]).is_empty());
+        assert!(decompose(&tiles("123m456p789s11z"), &[]).is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use crate::player::{PlayerType, Players};
+use crate::round::Round;
+use crate::rules::preset::Preset;
+use crate::rules::{Rules, RulesChoice};
+use crate::ryuukyoku::{self, AbortiveDrawKind};
+use crate::standings;
+use crate::turn::TurnPhase;
+use bevy::prelude::*;
+
+const FONT: &str = "fonts/DejaVuSans.ttf";
+const FONT_SIZE: f32 = 24.0;
+
+pub struct FuritenIndicator;
+
+pub struct RoundIndicator;
+
+pub struct NineTerminalsPrompt;
+
+pub struct SetupScreen;
+
+pub struct RulesChoiceText;
+
+pub fn setup_hud_system(commands: &mut Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(CameraUiBundle::default());
+
+    commands
+        .spawn(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text {
+                value: String::new(),
+                font: asset_server.load(FONT),
+                style: TextStyle {
+                    font_size: FONT_SIZE,
+                    color: Color::RED,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        })
+        .with(FuritenIndicator);
+
+    commands
+        .spawn(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text {
+                value: String::new(),
+                font: asset_server.load(FONT),
+                style: TextStyle {
+                    font_size: FONT_SIZE,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        })
+        .with(RoundIndicator);
+
+    commands
+        .spawn(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text {
+                value: String::new(),
+                font: asset_server.load(FONT),
+                style: TextStyle {
+                    font_size: FONT_SIZE,
+                    color: Color::WHITE,
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        })
+        .with(NineTerminalsPrompt);
+}
+
+pub fn round_indicator_system(
+    round: Res<Round>,
+    players: Res<Players>,
+    mut query: Query<&mut Text, With<RoundIndicator>>,
+) {
+    let mut value = format!(
+        "{:?} {} - {} honba - {} riichi sticks",
+        round.wind, round.hand, round.honba, round.riichi_sticks
+    );
+
+    for player in players.iter() {
+        value.push_str(&format!(
+            "\n{:?} ({:?}): {}",
+            player.seat, player.wind, player.points
+        ));
+    }
+
+    for mut text in query.iter_mut() {
+        if text.value != value {
+            text.value = value.clone();
+        }
+    }
+}
+
+pub fn final_standings_system(
+    commands: &mut Commands,
+    asset_server: Res<AssetServer>,
+    players: Res<Players>,
+    round: Res<Round>,
+    rules: Res<Rules>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let value = standings::final_standings(&players, round.riichi_sticks, &rules)
+        .iter()
+        .map(|standing| {
+            format!(
+                "{}. {:?}: {} ({})",
+                standing.placement,
+                standing.seat,
+                standing.points,
+                standings::format_thousands(standing.score)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    info!("Final standings:\n{}", value);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.7).into()),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text {
+                    value,
+                    font: asset_server.load(FONT),
+                    style: TextStyle {
+                        font_size: FONT_SIZE * 2.0,
+                        color: Color::WHITE,
+                        ..Default::default()
+                    },
+                },
+                ..Default::default()
+            });
+        });
+}
+
+pub fn spawn_setup_screen_system(
+    commands: &mut Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.add(Color::rgba(0.0, 0.0, 0.0, 0.7).into()),
+            ..Default::default()
+        })
+        .with(SetupScreen)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle {
+                    text: Text {
+                        value: String::new(),
+                        font: asset_server.load(FONT),
+                        style: TextStyle {
+                            font_size: FONT_SIZE * 2.0,
+                            color: Color::WHITE,
+                            ..Default::default()
+                        },
+                    },
+                    ..Default::default()
+                })
+                .with(RulesChoiceText);
+        });
+}
+
+pub fn rules_choice_system(
+    keyboard: Res<Input<KeyCode>>,
+    mut choice: ResMut<RulesChoice>,
+    mut query: Query<&mut Text, With<RulesChoiceText>>,
+) {
+    if keyboard.just_pressed(KeyCode::Up) {
+        choice.select_previous();
+    }
+
+    if keyboard.just_pressed(KeyCode::Down) {
+        choice.select_next();
+    }
+
+    let mut value = "Rules (Return to start)".to_string();
+
+    for option in RulesChoice::options() {
+        let marker = if option == choice.preset { ">" } else { " " };
+        let name = option.map_or("Rules file", Preset::name);
+        value.push_str(&format!("\n{} {}", marker, name));
+    }
+
+    for mut text in query.iter_mut() {
+        if text.value != value {
+            text.value = value.clone();
+        }
+    }
+}
+
+pub fn despawn_setup_screen_system(
+    commands: &mut Commands,
+    query: Query<Entity, With<SetupScreen>>,
+) {
+    for entity in query.iter() {
+        commands.despawn_recursive(entity);
+    }
+}
+
+pub fn furiten_indicator_system(
+    players: Res<Players>,
+    mut query: Query<&mut Text, With<FuritenIndicator>>,
+) {
+    let human = match players
+        .iter()
+        .find(|player| player.r#type == PlayerType::Human)
+    {
+        Some(human) => human,
+        None => return,
+    };
+
+    // The waits are only known while the hand is not missing a discard.
+    if human.tiles.len() % 3 != 1 {
+        return;
+    }
+
+    let value = if human.is_furiten() { "Furiten" } else { "" };
+
+    for mut text in query.iter_mut() {
+        if text.value != value {
+            text.value = value.to_string();
+        }
+    }
+}
+
+/// Offers the human to abort the hand with kyuushu kyuuhai while it is possible.
+pub fn nine_terminals_prompt_system(
+    state: Res<State<TurnPhase>>,
+    players: Res<Players>,
+    rules: Res<Rules>,
+    mut query: Query<&mut Text, With<NineTerminalsPrompt>>,
+) {
+    let player = players.current_player();
+
+    let can_declare = *state.current() == TurnPhase::Discard
+        && player.r#type == PlayerType::Human
+        && rules.abortive_draws.allows(AbortiveDrawKind::NineTerminals)
+        && ryuukyoku::can_declare_nine_terminals(player, &players);
+
+    let value = if can_declare {
+        "Nine terminals: press 9 to abort the hand"
+    } else {
+        ""
+    };
+
+    for mut text in query.iter_mut() {
+        if text.value != value {
+            text.value = value.to_string();
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::meld::calculate_meld_animations;
+use crate::player::{calculate_hand_animation, PlayerIdent, Players};
+use crate::rules::{KanDora, Rules};
+use crate::tiles::{Tile, Wind};
+use crate::wall::{Doras, RevealDora};
+use bevy::prelude::*;
+
+pub const MAX_KANS: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KanKind {
+    /// Ankan, all four tiles come from the hand.
+    Concealed,
+    /// Shouminkan, the fourth tile is added to an open pon.
+    Added,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DeclareKan {
+    seat: Wind,
+    kind: KanKind,
+    tile: Tile,
+}
+
+impl DeclareKan {
+    pub fn new(seat: Wind, kind: KanKind, tile: Tile) -> Self {
+        Self { seat, kind, tile }
+    }
+}
+
+pub fn declare_kan_system(
+    commands: &mut Commands,
+    mut players: ResMut<Players>,
+    mut doras: ResMut<Doras>,
+    rules: Res<Rules>,
+    events: Query<(Entity, &DeclareKan)>,
+    transform_query: Query<&Transform>,
+) {
+    for (event, &DeclareKan { seat, kind, tile }) in events.iter() {
+        players.break_ippatsu();
+        let player = players.player_by_ident_mut(PlayerIdent::Seat(seat));
+
+        match player.declare_kan(kind, tile) {
+            Ok(_) => {
+                info!(
+                    "Player sitting {:?} declared a {:?} kan of {:?}.",
+                    seat, kind, tile
+                );
+
+                for (entity, animation) in
+                    calculate_meld_animations(&player.melds, player.seat, &transform_query)
+                {
+                    commands.insert_one(entity, animation);
+                }
+
+                for (index, tile_entity) in player.tiles.iter().enumerate() {
+                    if let Ok(transform) = transform_query.get(tile_entity.entity) {
+                        let animation = calculate_hand_animation(
+                            player.hand_position(index),
+                            player.seat,
+                            *transform,
+                            false,
+                        );
+                        commands.insert_one(tile_entity.entity, animation);
+                    }
+                }
+
+                let immediately =
+                    kind == KanKind::Concealed || rules.kan_dora == KanDora::Immediate;
+
+                for _ in 0..doras.reveal_for_kan(immediately) {
+                    commands.spawn((RevealDora,));
+                }
+            }
+            Err(msg) => {
+                error!("{}", msg);
+            }
+        }
+
+        commands.despawn(event);
+    }
+}
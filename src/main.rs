@@ -1,8 +1,30 @@
+mod claim;
+mod cli;
+mod fairness;
+mod hand;
+mod hud;
+mod kan;
+mod meld;
 mod player;
+mod riichi;
+mod river;
+mod rng;
+mod round;
+mod rules;
+mod ryuukyoku;
+mod scoring;
+mod standings;
 mod tiles;
+mod turn;
 mod wall;
 
+use crate::claim::ClaimWindow;
+use crate::cli::Options;
 use crate::player::Players;
+use crate::rng::GameRng;
+use crate::round::{HandEndDelay, Round};
+use crate::rules::{Rules, RulesChoice};
+use crate::turn::{HandCursor, TurnPhase};
 use bevy::prelude::*;
 use bevy::render::camera::PerspectiveProjection;
 use bevy_easings::EasingsPlugin;
@@ -10,10 +32,31 @@ use bevy_easings::EasingsPlugin;
 #[derive(Debug, Clone, Copy)]
 pub enum GameState {
     Loading,
+    Setup,
     Play,
+    End,
 }
 
 fn main() {
+    let options = Options::parse(std::env::args().skip(1)).unwrap_or_else(|msg| exit_with(&msg));
+    tiles::use_ascii_tiles(options.ascii);
+    let custom_rules = Rules::load(&options.rules_file).unwrap_or_else(|msg| exit_with(&msg));
+    let mut rng = GameRng::new(options.seed.or(custom_rules.seed));
+    let rules_choice = RulesChoice::new(custom_rules, options.preset);
+    let rules = rules_choice.rules();
+
+    if let Some((seed, hash)) = &options.verify {
+        match fairness::verify(seed, hash, &rules) {
+            Ok(report) => {
+                println!("{}", report);
+                return;
+            }
+            Err(msg) => exit_with(&msg),
+        }
+    }
+
+    let players = Players::new(&rules, &mut rng);
+
     App::build()
         .add_resource(WindowDescriptor {
             title: "Mahjong".to_string(),
@@ -22,9 +65,18 @@ fn main() {
         .add_resource(Msaa { samples: 8 })
         .add_plugins(DefaultPlugins)
         .add_plugin(EasingsPlugin)
-        .add_resource(Players::new())
+        .add_resource(players)
         .add_resource(State::new(GameState::Loading))
+        .add_resource(State::new(TurnPhase::Idle))
+        .add_resource(Round::default())
+        .add_resource(HandCursor::default())
+        .add_resource(ClaimWindow::new(rules.claim_timeout))
+        .add_resource(HandEndDelay::default())
+        .add_resource(rules)
+        .add_resource(rules_choice)
+        .add_resource(rng)
         .add_startup_system(tiles::load_tile_asset_data_system.system())
+        .add_startup_system(hud::setup_hud_system.system())
         .add_stage_after(
             stage::UPDATE,
             "game_state",
@@ -33,20 +85,103 @@ fn main() {
                     GameState::Loading,
                     SystemStage::single(tiles::blend_tile_textures_system.system()),
                 )
+                .with_enter_stage(
+                    GameState::Setup,
+                    SystemStage::single(hud::spawn_setup_screen_system.system()),
+                )
+                .with_update_stage(
+                    GameState::Setup,
+                    SystemStage::parallel()
+                        .with_system(hud::rules_choice_system.system())
+                        .with_system(rules::confirm_rules_system.system()),
+                )
                 .with_exit_stage(
-                    GameState::Loading,
-                    Schedule::default().with_stage(
-                        "setup_game",
-                        SystemStage::parallel()
-                            .with_system(wall::build_wall_system.system())
-                            .with_system(player::draw_hand_system.system()),
-                    ),
+                    GameState::Setup,
+                    SystemStage::parallel()
+                        .with_system(hud::despawn_setup_screen_system.system())
+                        .with_system(turn::start_hand_system.system()),
                 )
                 .with_update_stage(
                     GameState::Play,
                     SystemStage::parallel()
                         .with_system(wall::Doras::reveal_system.system())
-                        .with_system(player::Players::draw_tile_system.system()),
+                        .with_system(wall::Doras::reveal_ura_system.system())
+                        .with_system(player::Players::draw_tile_system.system())
+                        .with_system(player::Players::discard_tile_system.system())
+                        .with_system(player::Players::sort_hand_system.system())
+                        .with_system(round::settle_win_system.system())
+                        .with_system(claim::call_tile_system.system())
+                        .with_system(kan::declare_kan_system.system())
+                        .with_system(riichi::deposit_riichi_stick_system.system())
+                        .with_system(hud::furiten_indicator_system.system())
+                        .with_system(hud::round_indicator_system.system())
+                        .with_system(hud::nine_terminals_prompt_system.system())
+                        .with_system(ryuukyoku::exhaustive_draw_system.system())
+                        .with_system(ryuukyoku::abortive_draw_system.system()),
+                )
+                .with_enter_stage(
+                    GameState::End,
+                    SystemStage::parallel()
+                        .with_system(hud::final_standings_system.system())
+                        .with_system(rng::log_seed_system.system()),
+                ),
+        )
+        .add_stage_after(
+            "game_state",
+            "turn_phase",
+            StateStage::<TurnPhase>::default()
+                .with_enter_stage(
+                    TurnPhase::Setup,
+                    SystemStage::parallel()
+                        .with_system(wall::build_wall_system.system())
+                        .with_system(player::draw_hand_system.system())
+                        .with_system(turn::start_deal_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::Deal,
+                    SystemStage::single(turn::wait_for_tiles_system.system()),
+                )
+                .with_enter_stage(
+                    TurnPhase::Draw,
+                    SystemStage::single(turn::draw_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::Draw,
+                    SystemStage::single(turn::wait_for_tiles_system.system()),
+                )
+                .with_enter_stage(
+                    TurnPhase::Discard,
+                    SystemStage::single(turn::start_discard_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::Discard,
+                    SystemStage::single(turn::discard_system.system()),
+                )
+                .with_enter_stage(
+                    TurnPhase::Claim,
+                    SystemStage::single(claim::open_claim_window_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::Claim,
+                    SystemStage::single(claim::claim_window_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::Call,
+                    SystemStage::single(turn::wait_for_tiles_system.system()),
+                )
+                .with_enter_stage(
+                    TurnPhase::NextPlayer,
+                    SystemStage::single(turn::next_player_system.system()),
+                )
+                .with_enter_stage(
+                    TurnPhase::HandEnd,
+                    SystemStage::parallel()
+                        .with_system(round::start_hand_end_system.system())
+                        .with_system(fairness::reveal_wall_seed_system.system()),
+                )
+                .with_update_stage(
+                    TurnPhase::HandEnd,
+                    SystemStage::single(round::hand_end_system.system()),
                 ),
         )
         .add_startup_system(setup.system())
@@ -82,3 +217,8 @@ fn setup(
         ..Default::default()
     });
 }
+
+fn exit_with(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1)
+}
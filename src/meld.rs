@@ -0,0 +1,197 @@
+use crate::hand::{Set, SetKind};
+use crate::player::calculate_rotation_from_seat;
+use crate::tiles::{TileAssetData, Wind};
+use crate::wall::{TileEntity, HALF_WALL_LENGTH};
+use bevy::prelude::*;
+use bevy_easings::{Ease, EaseFunction, EasingComponent, EasingType};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeldKind {
+    Chi,
+    Pon,
+    Kan,
+}
+
+#[derive(Debug, Clone)]
+pub struct Meld {
+    pub kind: MeldKind,
+    /// Tiles from left to right as they are laid out on the table.
+    pub tiles: Vec<TileEntity>,
+    /// Index of the called tile which is turned sideways.
+    pub called: Option<usize>,
+    /// Seat of the player the called tile was taken from.
+    pub from: Option<Wind>,
+    /// Index of the tile added to a called triplet, it lies next to the called tile.
+    pub added: Option<usize>,
+}
+
+impl Meld {
+    /// Lays out the tiles so the called tile points to the player it was taken from.
+    pub fn new(
+        kind: MeldKind,
+        seat: Wind,
+        from: Wind,
+        called: TileEntity,
+        mut tiles: Vec<TileEntity>,
+    ) -> Self {
+        let called_index = match relative_seat(seat, from) {
+            1 => 0,
+            2 => 1,
+            _ => tiles.len(),
+        };
+
+        tiles.insert(called_index, called);
+
+        Self {
+            kind,
+            tiles,
+            called: Some(called_index),
+            from: Some(from),
+            added: None,
+        }
+    }
+
+    pub fn concealed_kan(tiles: Vec<TileEntity>) -> Self {
+        Self {
+            kind: MeldKind::Kan,
+            tiles,
+            called: None,
+            from: None,
+            added: None,
+        }
+    }
+
+    pub fn add_kan_tile(&mut self, tile_entity: TileEntity) {
+        self.kind = MeldKind::Kan;
+        self.added = Some(self.tiles.len());
+        self.tiles.push(tile_entity);
+    }
+
+    /// Every meld except a concealed kan contains a tile called from another player.
+    pub fn is_open(&self) -> bool {
+        self.from.is_some()
+    }
+
+    pub fn is_concealed_kan(&self) -> bool {
+        self.kind == MeldKind::Kan && !self.is_open()
+    }
+
+    pub fn called_tile(&self) -> Option<TileEntity> {
+        self.called.map(|index| self.tiles[index])
+    }
+
+    pub fn set(&self) -> Set {
+        let kind = match self.kind {
+            MeldKind::Chi => SetKind::Sequence,
+            MeldKind::Pon => SetKind::Triplet,
+            MeldKind::Kan => SetKind::Quad,
+        };
+
+        let tile = self
+            .tiles
+            .iter()
+            .map(|tile_entity| tile_entity.tile)
+            .min()
+            .unwrap();
+
+        Set::new(kind, tile, self.is_open())
+    }
+}
+
+/// Number of turns `from` plays before `seat`, `1` being the player to the left.
+fn relative_seat(seat: Wind, from: Wind) -> usize {
+    (4 + seat as usize - from as usize) % 4
+}
+
+/// Melds lie face up in front of the hand and are stacked from the right corner to the left.
+/// The outer tiles of a concealed kan lie face down.
+fn calculate_meld_transforms(melds: &[Meld]) -> Vec<(Entity, Transform)> {
+    let z = HALF_WALL_LENGTH + TileAssetData::WIDTH + TileAssetData::DEPTH * 2.0;
+    let y = TileAssetData::HEIGHT / 2.0;
+    let mut right = HALF_WALL_LENGTH + TileAssetData::DEPTH;
+
+    let sideways = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+    let sideways_z = z + (TileAssetData::DEPTH - TileAssetData::WIDTH) / 2.0;
+
+    let mut transforms = Vec::new();
+
+    for meld in melds {
+        for (index, tile_entity) in meld.tiles.iter().enumerate().rev() {
+            if meld.added == Some(index) {
+                continue;
+            }
+
+            if meld.called == Some(index) {
+                let x = right - TileAssetData::DEPTH / 2.0;
+                right -= TileAssetData::DEPTH;
+
+                transforms.push((
+                    tile_entity.entity,
+                    Transform {
+                        translation: Vec3::new(x, y, sideways_z),
+                        rotation: sideways,
+                        scale: Vec3::one(),
+                    },
+                ));
+
+                if let Some(added) = meld.added {
+                    transforms.push((
+                        meld.tiles[added].entity,
+                        Transform {
+                            translation: Vec3::new(x, y, sideways_z - TileAssetData::WIDTH),
+                            rotation: sideways,
+                            scale: Vec3::one(),
+                        },
+                    ));
+                }
+            } else {
+                let x = right - TileAssetData::WIDTH / 2.0;
+                right -= TileAssetData::WIDTH;
+
+                let face_down =
+                    meld.is_concealed_kan() && (index == 0 || index == meld.tiles.len() - 1);
+                let rotation = if face_down {
+                    Quat::from_rotation_x(std::f32::consts::PI)
+                } else {
+                    Quat::identity()
+                };
+
+                transforms.push((
+                    tile_entity.entity,
+                    Transform {
+                        translation: Vec3::new(x, y, z),
+                        rotation,
+                        scale: Vec3::one(),
+                    },
+                ));
+            }
+        }
+    }
+
+    transforms
+}
+
+pub fn calculate_meld_animations(
+    melds: &[Meld],
+    seat: Wind,
+    transform_query: &Query<&Transform>,
+) -> Vec<(Entity, EasingComponent<Transform>)> {
+    let seat_rotation = Transform::from_rotation(calculate_rotation_from_seat(seat));
+
+    calculate_meld_transforms(melds)
+        .into_iter()
+        .filter_map(|(entity, transform_to)| {
+            let transform = transform_query.get(entity).ok()?;
+
+            let animation = transform.ease_to(
+                seat_rotation * transform_to,
+                EaseFunction::QuadraticOut,
+                EasingType::Once {
+                    duration: std::time::Duration::from_millis(500),
+                },
+            );
+
+            Some((entity, animation))
+        })
+        .collect()
+}
@@ -1,11 +1,25 @@
-use crate::tiles::{TileAssetData, Wind};
-use crate::wall::{TileEntity, Wall, HALF_WALL_LENGTH};
+use crate::hand::{Set, TILES_IN_HAND};
+use crate::kan::{KanKind, MAX_KANS};
+use crate::meld::{Meld, MeldKind};
+use crate::riichi::Riichi;
+use crate::river::{calculate_hand_to_river_animation, Discard, DiscardKind, River};
+use crate::rng::GameRng;
+use crate::rules::Rules;
+use crate::scoring::payment::RIICHI_STICK;
+use crate::tiles::counts::TileCounts;
+use crate::tiles::notation::{Notated, NotatedMeld, Notation};
+use crate::tiles::shanten;
+use crate::tiles::{Tile, TileAssetData, TileGroup, Wind};
+use crate::wall::{Doras, Kans, RevealDora, TileEntity, Wall, HALF_WALL_LENGTH};
 use bevy::prelude::*;
-use bevy_easings::{Ease, EaseFunction, EaseMethod, EasingChainComponent, EasingType};
+use bevy_easings::{
+    Ease, EaseFunction, EaseMethod, EasingChainComponent, EasingComponent, EasingType,
+};
 use rand::Rng;
 
-const TILES_IN_HAND: usize = 13;
-const MAX_TILES_IN_HAND: usize = TILES_IN_HAND + 1;
+/// Number of tiles the hand is centered on.
+const HAND_LAYOUT_WIDTH: usize = 13;
+const TILES_PER_DEAL: usize = 4;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PlayerType {
@@ -18,32 +32,464 @@ pub struct Player {
     pub r#type: PlayerType,
     pub seat: Wind,
     pub wind: Wind,
+    /// Seat wind in the first hand of the match.
+    pub starting_wind: Wind,
     pub points: i32,
     pub tiles: Vec<TileEntity>,
+    pub river: River,
+    pub melds: Vec<Meld>,
+    pub riichi: Option<Riichi>,
+    max_tiles: usize,
+    drawn: Option<Entity>,
+    /// Set after a kan until the replacement tile is drawn from the dead wall.
+    needs_replacement: bool,
+    /// Set while the last drawn tile is a replacement tile.
+    rinshan: bool,
+    /// Tile added to a pon, the others may rob it until the replacement tile is drawn.
+    added_kan_tile: Option<TileEntity>,
+    /// Set after letting a winning tile pass, lasts until the next own discard or for the rest
+    /// of the hand after riichi.
+    missed_win: bool,
 }
 
 impl Player {
-    pub fn new(seat: Wind, wind: Wind, r#type: PlayerType) -> Self {
+    pub fn new(seat: Wind, wind: Wind, r#type: PlayerType, rules: &Rules) -> Self {
+        let max_tiles = TILES_IN_HAND + 1;
+
         Self {
             seat,
             wind,
+            starting_wind: wind,
             r#type,
-            points: 25_000,
-            tiles: Vec::with_capacity(MAX_TILES_IN_HAND),
+            points: rules.starting_points,
+            tiles: Vec::with_capacity(max_tiles),
+            river: River::default(),
+            melds: Vec::new(),
+            riichi: None,
+            max_tiles,
+            drawn: None,
+            needs_replacement: false,
+            rinshan: false,
+            added_kan_tile: None,
+            missed_win: false,
         }
     }
 
-    fn add_tiles(&mut self, tiles: &[TileEntity]) -> Result<(), String> {
+    fn clear_hand(&mut self) {
+        self.tiles.clear();
+        self.river = River::default();
+        self.melds.clear();
+        self.riichi = None;
+        self.drawn = None;
+        self.needs_replacement = false;
+        self.rinshan = false;
+        self.added_kan_tile = None;
+        self.missed_win = false;
+    }
+
+    fn add_tiles(&mut self, tiles: &[TileEntity], replacement: bool) -> Result<(), String> {
         let new_len = self.tiles.len() + tiles.len();
 
-        if new_len > MAX_TILES_IN_HAND {
-            let msg = format!("Player sitting {:?} cannot draw {} tiles as there are already {} tiles in his hand ({} > {})!", self.seat, tiles.len(), self.tiles.len(), new_len, MAX_TILES_IN_HAND);
+        if new_len > self.max_tiles {
+            let msg = format!("Player sitting {:?} cannot draw {} tiles as there are already {} tiles in his hand ({} > {})!", self.seat, tiles.len(), self.tiles.len(), new_len, self.max_tiles);
             Err(msg)
         } else {
             self.tiles.extend_from_slice(tiles);
+            self.drawn = tiles.last().map(|tile_entity| tile_entity.entity);
+            self.needs_replacement = false;
+            self.rinshan = replacement;
+            self.added_kan_tile = None;
             Ok(())
         }
     }
+
+    pub fn hand_tiles(&self) -> Vec<Tile> {
+        self.tiles
+            .iter()
+            .map(|tile_entity| tile_entity.tile)
+            .collect()
+    }
+
+    /// The hand and melds in the `123m456p789s1234567z` notation.
+    pub fn notation(&self) -> Notation {
+        let notate = |tiles: &[TileEntity]| {
+            tiles
+                .iter()
+                .map(|tile_entity| Notated {
+                    tile: tile_entity.tile,
+                    red: tile_entity.red,
+                })
+                .collect()
+        };
+
+        Notation {
+            tiles: notate(&self.tiles),
+            melds: self
+                .melds
+                .iter()
+                .map(|meld| NotatedMeld {
+                    tiles: notate(&meld.tiles),
+                    open: meld.is_open(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The drawn tile while the hand is full, it is kept apart at the right end until the
+    /// discard.
+    fn separated_tile(&self) -> Option<Entity> {
+        let hand_size = self.tiles.len() + self.melds.len() * 3;
+        self.drawn.filter(|_| hand_size == self.max_tiles)
+    }
+
+    /// Position of the tile in the hand layout which leaves a gap before the drawn tile.
+    pub fn hand_position(&self, index: usize) -> usize {
+        let entity = self.tiles.get(index).map(|tile_entity| tile_entity.entity);
+        let is_separated = entity.is_some() && entity == self.separated_tile();
+
+        index + is_separated as usize
+    }
+
+    /// Riipai, sorts the hand except for the drawn tile.
+    pub fn sort_tiles(&mut self, order: &[TileGroup]) {
+        let separated = self.separated_tile();
+
+        self.tiles.sort_by_key(|tile_entity| {
+            (
+                Some(tile_entity.entity) == separated,
+                tile_entity.tile.sort_key(order),
+            )
+        });
+    }
+
+    pub fn has_drawn(&self) -> bool {
+        self.drawn.is_some()
+    }
+
+    pub fn needs_replacement(&self) -> bool {
+        self.needs_replacement
+    }
+
+    pub fn is_rinshan(&self) -> bool {
+        self.rinshan
+    }
+
+    pub fn added_kan_tile(&self) -> Option<TileEntity> {
+        self.added_kan_tile
+    }
+
+    /// The tile the others may win on, the tile just added to a kan or else the last discard.
+    pub fn claimable_tile(&self) -> Option<TileEntity> {
+        self.added_kan_tile
+            .or_else(|| self.river.last().map(|discard| discard.tile_entity))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.melds.iter().all(Meld::is_concealed_kan)
+    }
+
+    /// Tiles which complete the hand, only known while it is not the player's turn.
+    pub fn winning_tiles(&self) -> Vec<Tile> {
+        if self.tiles.len() % 3 != 1 {
+            return Vec::new();
+        }
+
+        let counts = TileCounts::from(self.hand_tiles().as_slice());
+        shanten::winning_tiles(&counts, self.melds.len())
+    }
+
+    /// A furiten player cannot win by ron, either because one of the winning tiles lies in the
+    /// own river or because a winning tile was let pass.
+    pub fn is_furiten(&self) -> bool {
+        if self.missed_win {
+            return true;
+        }
+
+        let winning_tiles = self.winning_tiles();
+        self.river
+            .iter()
+            .any(|discard| winning_tiles.contains(&discard.tile()))
+    }
+
+    pub fn miss_win(&mut self) {
+        self.missed_win = true;
+    }
+
+    pub fn can_declare_riichi(&self) -> bool {
+        self.riichi.is_none() && self.is_closed() && self.points >= RIICHI_STICK
+    }
+
+    /// Indices of the tiles whose discard leaves the hand tenpai.
+    pub fn riichi_discards(&self) -> Vec<usize> {
+        if !self.can_declare_riichi() {
+            return Vec::new();
+        }
+
+        let counts = TileCounts::from(self.hand_tiles().as_slice());
+
+        (0..self.tiles.len())
+            .filter(|&index| {
+                let mut counts = counts;
+                counts.remove(self.tiles[index].tile).is_ok()
+                    && shanten::shanten(&counts, self.melds.len()) == 0
+            })
+            .collect()
+    }
+
+    /// The AI keeps the lowest shanten and the most unseen tiles which improve the hand, after
+    /// riichi only the drawn tile can be discarded.
+    pub fn ai_discard(&self, visible: &TileCounts) -> usize {
+        let last = self.tiles.len() - 1;
+
+        if self.riichi.is_some() {
+            return last;
+        }
+
+        let counts = TileCounts::from(self.hand_tiles().as_slice());
+        let melds = self.melds.len();
+
+        // On ties the rightmost tile is discarded, which is the drawn tile if there is one.
+        (0..self.tiles.len())
+            .rev()
+            .filter_map(|index| {
+                let mut counts = counts;
+                counts.remove(self.tiles[index].tile).ok()?;
+
+                let shanten = shanten::shanten(&counts, melds);
+                let unseen = shanten::ukeire(&counts, melds, visible)
+                    .iter()
+                    .map(|ukeire| ukeire.unseen as usize)
+                    .sum::<usize>();
+
+                Some((index, shanten, std::cmp::Reverse(unseen)))
+            })
+            .min_by_key(|&(_, shanten, unseen)| (shanten, unseen))
+            .map_or(last, |(index, _, _)| index)
+    }
+
+    /// Thirteen orphans is still worth going for when it is at most two tiles away.
+    pub fn ai_wants_nine_terminals(&self) -> bool {
+        let counts = TileCounts::from(self.hand_tiles().as_slice());
+        shanten::thirteen_orphans_shanten(&counts) > 2
+    }
+
+    /// The first kan which does not raise the shanten. Added kans are skipped while `chankan_risk`
+    /// as the added tile could be robbed.
+    pub fn ai_kan(&self, chankan_risk: bool) -> Option<(KanKind, Tile)> {
+        let counts = TileCounts::from(self.hand_tiles().as_slice());
+        let melds = self.melds.len();
+        let before = shanten::shanten(&counts, melds);
+
+        self.kan_options().into_iter().find(|&(kind, tile)| {
+            let (removed, melds) = match kind {
+                KanKind::Concealed => (4, melds + 1),
+                KanKind::Added if chankan_risk => return false,
+                KanKind::Added => (1, melds),
+            };
+
+            let mut after = counts;
+            (0..removed).all(|_| after.remove(tile).is_ok())
+                && shanten::shanten(&after, melds) <= before
+        })
+    }
+
+    pub fn meld_sets(&self) -> Vec<Set> {
+        self.melds.iter().map(Meld::set).collect()
+    }
+
+    pub fn call(
+        &mut self,
+        kind: MeldKind,
+        from: Wind,
+        called: TileEntity,
+        indices: &[usize],
+    ) -> Result<(), String> {
+        if indices.iter().any(|&index| index >= self.tiles.len()) {
+            let msg = format!(
+                "Player sitting {:?} cannot call with tiles {:?} as there are only {} tiles in his hand!",
+                self.seat,
+                indices,
+                self.tiles.len()
+            );
+            return Err(msg);
+        }
+
+        let mut indices = indices.to_vec();
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut tiles = indices
+            .into_iter()
+            .map(|index| self.tiles.remove(index))
+            .collect::<Vec<_>>();
+        tiles.sort_by_key(|tile_entity| tile_entity.tile);
+
+        self.melds
+            .push(Meld::new(kind, self.seat, from, called, tiles));
+        self.drawn = None;
+        self.needs_replacement = kind == MeldKind::Kan;
+
+        Ok(())
+    }
+
+    /// Kans which can be declared right after drawing a tile.
+    pub fn kan_options(&self) -> Vec<(KanKind, Tile)> {
+        if self.drawn.is_none() {
+            return Vec::new();
+        }
+
+        let mut options = Vec::new();
+
+        for (index, tile_entity) in self.tiles.iter().enumerate() {
+            let tile = tile_entity.tile;
+            let is_first = self.tiles[..index].iter().all(|other| other.tile != tile);
+
+            if !is_first {
+                continue;
+            }
+
+            let count = self.tiles.iter().filter(|other| other.tile == tile).count();
+
+            if count == 4 {
+                options.push((KanKind::Concealed, tile));
+            }
+
+            let has_pon = self
+                .melds
+                .iter()
+                .any(|meld| meld.kind == MeldKind::Pon && meld.tiles[0].tile == tile);
+
+            if has_pon {
+                options.push((KanKind::Added, tile));
+            }
+        }
+
+        if self.riichi.is_some() {
+            options.retain(|&(kind, tile)| {
+                kind == KanKind::Concealed && self.is_waits_keeping_kan(tile)
+            });
+        }
+
+        options
+    }
+
+    /// A kan after riichi must use the drawn tile and must not change the waits.
+    fn is_waits_keeping_kan(&self, tile: Tile) -> bool {
+        let drawn = self
+            .tiles
+            .iter()
+            .find(|tile_entity| Some(tile_entity.entity) == self.drawn);
+
+        if drawn.map(|tile_entity| tile_entity.tile) != Some(tile) {
+            return false;
+        }
+
+        let before = self
+            .tiles
+            .iter()
+            .filter(|tile_entity| Some(tile_entity.entity) != self.drawn)
+            .map(|tile_entity| tile_entity.tile)
+            .collect::<Vec<_>>();
+
+        let after = before
+            .iter()
+            .copied()
+            .filter(|&other| other != tile)
+            .collect::<Vec<_>>();
+
+        let waits_before =
+            shanten::winning_tiles(&TileCounts::from(before.as_slice()), self.melds.len());
+        let waits_after =
+            shanten::winning_tiles(&TileCounts::from(after.as_slice()), self.melds.len() + 1);
+
+        !waits_before.is_empty() && waits_before == waits_after
+    }
+
+    pub fn declare_kan(&mut self, kind: KanKind, tile: Tile) -> Result<(), String> {
+        if !self.kan_options().contains(&(kind, tile)) {
+            let msg = format!(
+                "Player sitting {:?} cannot declare a {:?} kan of {:?}!",
+                self.seat, kind, tile
+            );
+            return Err(msg);
+        }
+
+        let mut tiles = Vec::new();
+        let mut index = 0;
+
+        while index < self.tiles.len() {
+            if self.tiles[index].tile == tile {
+                tiles.push(self.tiles.remove(index));
+            } else {
+                index += 1;
+            }
+        }
+
+        match kind {
+            KanKind::Concealed => {
+                self.melds.push(Meld::concealed_kan(tiles));
+            }
+            KanKind::Added => {
+                let meld = self
+                    .melds
+                    .iter_mut()
+                    .find(|meld| meld.kind == MeldKind::Pon && meld.tiles[0].tile == tile)
+                    .unwrap();
+                meld.add_kan_tile(tiles[0]);
+                self.added_kan_tile = Some(tiles[0]);
+            }
+        }
+
+        self.drawn = None;
+        self.needs_replacement = true;
+
+        Ok(())
+    }
+
+    fn discard(
+        &mut self,
+        index: usize,
+        riichi: Option<Riichi>,
+    ) -> Result<(Discard, usize), String> {
+        if index < self.tiles.len() {
+            let tile_entity = self.tiles.remove(index);
+
+            let kind = if self.drawn == Some(tile_entity.entity) {
+                DiscardKind::Tsumogiri
+            } else {
+                DiscardKind::Tedashi
+            };
+            self.drawn = None;
+            self.rinshan = false;
+
+            if self.riichi.is_none() {
+                self.missed_win = false;
+            }
+
+            if riichi.is_some() {
+                self.riichi = riichi;
+            } else if let Some(riichi) = self.riichi.as_mut() {
+                riichi.ippatsu = false;
+            }
+
+            let discard = Discard {
+                tile_entity,
+                kind,
+                called_by: None,
+                riichi: riichi.is_some(),
+            };
+            let river_index = self.river.push(discard);
+
+            Ok((discard, river_index))
+        } else {
+            let msg = format!(
+                "Player sitting {:?} cannot discard tile {} as there are only {} tiles in his hand!",
+                self.seat,
+                index,
+                self.tiles.len()
+            );
+            Err(msg)
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -54,10 +500,8 @@ pub struct Players {
 }
 
 impl Players {
-    pub fn new() -> Self {
-        let wind = Wind::iter()
-            .cycle()
-            .skip(rand::thread_rng().gen_range(0..4));
+    pub fn new(rules: &Rules, rng: &mut GameRng) -> Self {
+        let wind = Wind::iter().cycle().skip(rng.gen_range(0..4));
 
         let players: Vec<Player> = Wind::iter()
             .zip(wind)
@@ -68,7 +512,7 @@ impl Players {
                     PlayerType::Ai
                 };
 
-                Player::new(seat, wind, player_type)
+                Player::new(seat, wind, player_type, rules)
             })
             .collect();
 
@@ -97,10 +541,6 @@ impl Players {
         &self.players[self.current]
     }
 
-    pub fn current_player_mut(&mut self) -> &mut Player {
-        &mut self.players[self.current]
-    }
-
     pub fn player_by_ident(&self, ident: PlayerIdent) -> &Player {
         self.players
             .iter()
@@ -121,6 +561,102 @@ impl Players {
             .unwrap()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter()
+    }
+
+    pub fn set_current(&mut self, ident: PlayerIdent) {
+        self.current = self
+            .players
+            .iter()
+            .position(|player| match ident {
+                PlayerIdent::Seat(seat) => player.seat == seat,
+                PlayerIdent::Wind(wind) => player.wind == wind,
+            })
+            .unwrap();
+    }
+
+    pub fn kan_count(&self) -> usize {
+        self.players
+            .iter()
+            .flat_map(|player| player.melds.iter())
+            .filter(|meld| meld.kind == MeldKind::Kan)
+            .count()
+    }
+
+    /// Tiles every player can see, the rivers without called tiles, all melds and the dora
+    /// indicators.
+    pub fn visible_tiles(&self, doras: &Doras) -> TileCounts {
+        let mut tiles = doras.indicators();
+
+        for player in self.players.iter() {
+            let discards = player
+                .river
+                .iter()
+                .filter(|discard| discard.called_by.is_none())
+                .map(Discard::tile);
+            let melds = player
+                .melds
+                .iter()
+                .flat_map(|meld| meld.tiles.iter().map(|tile_entity| tile_entity.tile));
+
+            tiles.extend(discards.chain(melds));
+        }
+
+        TileCounts::from(tiles.as_slice())
+    }
+
+    pub fn can_declare_kan(&self) -> bool {
+        self.kan_count() < MAX_KANS
+    }
+
+    /// Four kans by more than one player end the hand in an abortive draw.
+    pub fn has_four_kans_abort(&self) -> bool {
+        let players_with_kans = self
+            .players
+            .iter()
+            .filter(|player| player.melds.iter().any(|meld| meld.kind == MeldKind::Kan))
+            .count();
+
+        self.kan_count() == MAX_KANS && players_with_kans > 1
+    }
+
+    pub fn has_calls(&self) -> bool {
+        self.players.iter().any(|player| !player.melds.is_empty())
+    }
+
+    /// Every call interrupts the turn order and ends all ippatsu chances.
+    pub fn break_ippatsu(&mut self) {
+        for riichi in self
+            .players
+            .iter_mut()
+            .filter_map(|player| player.riichi.as_mut())
+        {
+            riichi.ippatsu = false;
+        }
+    }
+
+    /// The next player becomes dealer and everyone's seat wind moves on.
+    pub fn rotate_dealer(&mut self) {
+        self.dealer = (self.dealer + 1) % self.players.len();
+
+        for (offset, wind) in Wind::iter().enumerate() {
+            let index = (self.dealer + offset) % self.players.len();
+            self.players[index].wind = wind;
+        }
+
+        info!("Dealer sits {:?}.", self.dealer().seat);
+    }
+
+    /// Clears all hands, rivers and melds for the next hand which starts with the dealer.
+    pub fn start_hand(&mut self) {
+        for player in self.players.iter_mut() {
+            player.clear_hand();
+        }
+
+        self.current = self.dealer;
+    }
+
     pub fn end_turn(&mut self) {
         self.current = (self.current + 1) % self.players.len();
     }
@@ -129,6 +665,7 @@ impl Players {
         commands: &mut Commands,
         mut players: ResMut<Players>,
         mut wall: ResMut<Wall>,
+        mut kans: ResMut<Kans>,
         events: Query<(Entity, &DrawTiles)>,
         transform_query: Query<&Transform>,
     ) {
@@ -138,20 +675,47 @@ impl Players {
                 ident,
                 amount,
                 delay,
+                replacement,
             },
         ) in events.iter()
         {
-            let tiles = wall.draw(amount);
+            let tiles = if replacement {
+                kans.draw().map(|tile_entity| {
+                    wall.shift_dead_wall();
+                    vec![tile_entity]
+                })
+            } else {
+                wall.draw(amount)
+            };
+
+            let tiles = match tiles {
+                Ok(tiles) => tiles,
+                Err(msg) => {
+                    error!("{}", msg);
+                    commands.despawn(event);
+                    continue;
+                }
+            };
+
             let player = players.player_by_ident_mut(ident);
             let current_number_of_tiles = player.tiles.len();
 
-            match player.add_tiles(&tiles) {
+            match player.add_tiles(&tiles, replacement) {
                 Ok(_) => {
+                    info!(
+                        "Player sitting {:?} drew {}.",
+                        player.seat,
+                        tiles
+                            .iter()
+                            .map(|tile_entity| tile_entity.tile.to_string())
+                            .collect::<String>()
+                    );
+
                     for (index, tile_entity) in tiles.iter().enumerate() {
                         match transform_query.get(tile_entity.entity) {
                             Ok(transform) => {
                                 let animation = calculate_wall_to_hand_animation(
-                                    index + current_number_of_tiles,
+                                    player.hand_position(index + current_number_of_tiles),
                                     player.seat,
                                     *transform,
                                     delay,
@@ -163,6 +727,78 @@ impl Players {
                             }
                         }
                     }
+
+                    commands.spawn((SortHand { ident },));
+                }
+                Err(msg) => {
+                    error!("{}", msg);
+                }
+            }
+
+            commands.despawn(event);
+        }
+    }
+
+    pub fn discard_tile_system(
+        commands: &mut Commands,
+        mut players: ResMut<Players>,
+        mut doras: ResMut<Doras>,
+        events: Query<(Entity, &DiscardTile)>,
+        transform_query: Query<&Transform>,
+    ) {
+        for (
+            event,
+            &DiscardTile {
+                ident,
+                index,
+                riichi,
+            },
+        ) in events.iter()
+        {
+            let has_calls = players.has_calls();
+            let player = players.player_by_ident_mut(ident);
+
+            let riichi = if riichi {
+                let double = player.river.is_empty() && !has_calls;
+                Some(Riichi::new(double))
+            } else {
+                None
+            };
+
+            match player.discard(index, riichi) {
+                Ok((discard, river_index)) => {
+                    info!(
+                        "Player sitting {:?} discarded {} ({:?}).",
+                        player.seat,
+                        discard.tile(),
+                        discard.kind
+                    );
+
+                    if riichi.is_some() {
+                        info!("Player sitting {:?} declared riichi.", player.seat);
+                    }
+
+                    for _ in 0..doras.take_delayed() {
+                        commands.spawn((RevealDora,));
+                    }
+
+                    let tile_entity = discard.tile_entity;
+                    match transform_query.get(tile_entity.entity) {
+                        Ok(transform) => {
+                            let animation = calculate_hand_to_river_animation(
+                                river_index,
+                                player.river.sideways(),
+                                player.seat,
+                                *transform,
+                            );
+                            commands.insert_one(tile_entity.entity, animation);
+                        }
+                        Err(err) => {
+                            error!("Could not query tile because of {:?}!", err)
+                        }
+                    }
+
+                    commands.spawn((SortHand { ident },));
                 }
                 Err(msg) => {
                     error!("{}", msg);
@@ -172,6 +808,76 @@ impl Players {
             commands.despawn(event);
         }
     }
+
+    /// Sorts the hand once its tiles came to rest and slides them to their new places.
+    pub fn sort_hand_system(
+        commands: &mut Commands,
+        mut players: ResMut<Players>,
+        rules: Res<Rules>,
+        events: Query<(Entity, &SortHand)>,
+        easings: Query<Entity, With<EasingComponent<Transform>>>,
+        easing_chains: Query<Entity, With<EasingChainComponent<Transform>>>,
+        transform_query: Query<&Transform>,
+    ) {
+        for (event, &SortHand { ident }) in events.iter() {
+            let player = players.player_by_ident_mut(ident);
+
+            let is_moving = player.tiles.iter().any(|tile_entity| {
+                easings.get(tile_entity.entity).is_ok()
+                    || easing_chains.get(tile_entity.entity).is_ok()
+            });
+
+            if is_moving {
+                continue;
+            }
+
+            player.sort_tiles(&rules.tile_order);
+
+            for (index, tile_entity) in player.tiles.iter().enumerate() {
+                if let Ok(transform) = transform_query.get(tile_entity.entity) {
+                    let animation = calculate_hand_animation(
+                        player.hand_position(index),
+                        player.seat,
+                        *transform,
+                        false,
+                    );
+                    commands.insert_one(tile_entity.entity, animation);
+                }
+            }
+
+            commands.despawn(event);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DiscardTile {
+    ident: PlayerIdent,
+    index: usize,
+    riichi: bool,
+}
+
+impl DiscardTile {
+    pub fn new(ident: PlayerIdent, index: usize) -> Self {
+        Self {
+            ident,
+            index,
+            riichi: false,
+        }
+    }
+
+    pub fn riichi(ident: PlayerIdent, index: usize) -> Self {
+        Self {
+            ident,
+            index,
+            riichi: true,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SortHand {
+    ident: PlayerIdent,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -179,6 +885,8 @@ pub struct DrawTiles {
     ident: PlayerIdent,
     amount: usize,
     delay: Option<std::time::Duration>,
+    /// Draws from the dead wall after a kan.
+    replacement: bool,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -188,11 +896,21 @@ pub enum PlayerIdent {
 }
 
 impl DrawTiles {
-    fn new(ident: PlayerIdent, amount: usize) -> Self {
+    pub fn new(ident: PlayerIdent, amount: usize) -> Self {
         Self {
             ident,
             amount,
             delay: None,
+            replacement: false,
+        }
+    }
+
+    pub fn replacement(ident: PlayerIdent) -> Self {
+        Self {
+            ident,
+            amount: 1,
+            delay: None,
+            replacement: true,
         }
     }
 
@@ -201,12 +919,13 @@ impl DrawTiles {
             ident,
             amount,
             delay: Some(delay),
+            replacement: false,
         }
     }
 }
 
 fn calculate_hand_transform_from_index(index: usize) -> Transform {
-    let half_hand_length = TileAssetData::WIDTH * (TILES_IN_HAND as f32) / 2.0;
+    let half_hand_length = TileAssetData::WIDTH * (HAND_LAYOUT_WIDTH as f32) / 2.0;
     let x = index as f32 * TileAssetData::WIDTH + TileAssetData::WIDTH / 2.0 - half_hand_length;
 
     let y = TileAssetData::DEPTH / 2.0;
@@ -220,7 +939,7 @@ fn calculate_hand_transform_from_index(index: usize) -> Transform {
     }
 }
 
-fn calculate_rotation_from_seat(seat: Wind) -> Quat {
+pub fn calculate_rotation_from_seat(seat: Wind) -> Quat {
     let angle = match seat {
         Wind::East => -std::f32::consts::FRAC_PI_2,
         Wind::South => 0.0,
@@ -231,6 +950,27 @@ fn calculate_rotation_from_seat(seat: Wind) -> Quat {
     Quat::from_rotation_y(angle)
 }
 
+pub fn calculate_hand_animation(
+    index: usize,
+    seat: Wind,
+    transform: Transform,
+    lifted: bool,
+) -> EasingComponent<Transform> {
+    let mut transform_to = calculate_hand_transform_from_index(index);
+
+    if lifted {
+        transform_to.translation.y += TileAssetData::HEIGHT / 2.0;
+    }
+
+    transform.ease_to(
+        Transform::from_rotation(calculate_rotation_from_seat(seat)) * transform_to,
+        EaseFunction::QuadraticOut,
+        EasingType::Once {
+            duration: std::time::Duration::from_millis(200),
+        },
+    )
+}
+
 fn calculate_wall_to_hand_animation(
     index: usize,
     seat: Wind,
@@ -288,11 +1028,13 @@ fn calculate_wall_to_hand_animation(
 }
 
 pub fn draw_hand_system(commands: &mut Commands) {
-    for round in 0..4 {
+    let rounds = (TILES_IN_HAND + TILES_PER_DEAL - 1) / TILES_PER_DEAL;
+
+    for round in 0..rounds {
         for (i, wind) in Wind::iter().enumerate() {
             commands.spawn((DrawTiles::new_with_delay(
                 PlayerIdent::Wind(wind),
-                if round != 3 { 4 } else { 1 },
+                (TILES_IN_HAND - round * TILES_PER_DEAL).min(TILES_PER_DEAL),
                 std::time::Duration::from_millis((round * 4 + i) as u64 * 500),
             ),));
         }
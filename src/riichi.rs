@@ -0,0 +1,86 @@
+use crate::player::{calculate_rotation_from_seat, PlayerIdent, Players};
+use crate::river::TILES_PER_ROW;
+use crate::round::Round;
+use crate::scoring::payment::RIICHI_STICK;
+use crate::tiles::{TileAssetData, Wind};
+use bevy::prelude::*;
+
+/// Riichi can only be declared while at least this many tiles are left in the wall.
+pub const MIN_TILES_FOR_RIICHI: usize = 4;
+
+const STICK_LENGTH: f32 = 0.1;
+const STICK_HEIGHT: f32 = 0.004;
+const STICK_WIDTH: f32 = 0.01;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Riichi {
+    /// Declared with the first discard before any call was made.
+    pub double: bool,
+    /// Lasts until the next own discard or any call.
+    pub ippatsu: bool,
+}
+
+impl Riichi {
+    pub fn new(double: bool) -> Self {
+        Self {
+            double,
+            ippatsu: true,
+        }
+    }
+}
+
+pub struct RiichiStick;
+
+#[derive(Debug, Copy, Clone)]
+pub struct DepositRiichiStick {
+    seat: Wind,
+}
+
+impl DepositRiichiStick {
+    pub fn new(seat: Wind) -> Self {
+        Self { seat }
+    }
+}
+
+pub fn deposit_riichi_stick_system(
+    commands: &mut Commands,
+    mut players: ResMut<Players>,
+    mut round: ResMut<Round>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    events: Query<(Entity, &DepositRiichiStick)>,
+) {
+    for (event, &DepositRiichiStick { seat }) in events.iter() {
+        let player = players.player_by_ident_mut(PlayerIdent::Seat(seat));
+        player.points -= RIICHI_STICK;
+        round.riichi_sticks += 1;
+
+        info!(
+            "Player sitting {:?} deposited a riichi stick, {} on the table.",
+            seat, round.riichi_sticks
+        );
+
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box::new(
+                    STICK_LENGTH,
+                    STICK_HEIGHT,
+                    STICK_WIDTH,
+                ))),
+                material: materials.add(Color::WHITE.into()),
+                transform: calculate_stick_transform(seat),
+                ..Default::default()
+            })
+            .with(RiichiStick);
+
+        commands.despawn(event);
+    }
+}
+
+/// The stick lies just in front of the river of the player.
+fn calculate_stick_transform(seat: Wind) -> Transform {
+    let z = TileAssetData::WIDTH * TILES_PER_ROW as f32 / 2.0 - STICK_WIDTH * 2.0;
+
+    Transform::from_rotation(calculate_rotation_from_seat(seat))
+        * Transform::from_translation(Vec3::new(0.0, STICK_HEIGHT / 2.0, z))
+}
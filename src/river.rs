@@ -0,0 +1,175 @@
+use crate::player::calculate_rotation_from_seat;
+use crate::tiles::{Tile, TileAssetData, Wind};
+use crate::wall::TileEntity;
+use bevy::prelude::*;
+use bevy_easings::{Ease, EaseFunction, EaseMethod, EasingChainComponent, EasingType};
+
+pub const TILES_PER_ROW: usize = 6;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DiscardKind {
+    Tedashi,
+    Tsumogiri,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Discard {
+    pub tile_entity: TileEntity,
+    pub kind: DiscardKind,
+    /// Seat of the player who called the tile out of the river.
+    pub called_by: Option<Wind>,
+    /// Declares riichi, the tile is turned sideways.
+    pub riichi: bool,
+}
+
+impl Discard {
+    pub fn tile(&self) -> Tile {
+        self.tile_entity.tile
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct River {
+    discards: Vec<Discard>,
+}
+
+impl River {
+    /// Records the discard and returns its position in the river. Called tiles are no longer
+    /// part of the river so later discards close the gap.
+    pub fn push(&mut self, discard: Discard) -> usize {
+        self.discards.push(discard);
+        self.discards
+            .iter()
+            .filter(|discard| discard.called_by.is_none())
+            .count()
+            - 1
+    }
+
+    pub fn call_last(&mut self, seat: Wind) -> Option<TileEntity> {
+        let discard = self.discards.last_mut()?;
+
+        if discard.called_by.is_some() {
+            return None;
+        }
+
+        discard.called_by = Some(seat);
+        Some(discard.tile_entity)
+    }
+
+    /// Position of the sideways tile in the river. If the riichi declaration tile was called
+    /// the next discard is turned sideways instead.
+    pub fn sideways(&self) -> Option<usize> {
+        let declaration = self.discards.iter().position(|discard| discard.riichi)?;
+
+        let index = self.discards[declaration..]
+            .iter()
+            .position(|discard| discard.called_by.is_none())?
+            + declaration;
+
+        Some(
+            self.discards[..index]
+                .iter()
+                .filter(|discard| discard.called_by.is_none())
+                .count(),
+        )
+    }
+
+    pub fn last(&self) -> Option<&Discard> {
+        self.discards.last()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Discard> {
+        self.discards.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.discards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.discards.is_empty()
+    }
+}
+
+/// Tiles behind a sideways tile in the same row are pushed to the right.
+fn calculate_river_transform_from_index(index: usize, sideways: Option<usize>) -> Transform {
+    let half_row_length = TileAssetData::WIDTH * TILES_PER_ROW as f32 / 2.0;
+
+    let column = index % TILES_PER_ROW;
+    let row = index / TILES_PER_ROW;
+
+    let sideways_offset = match sideways {
+        Some(sideways) if sideways < index && sideways / TILES_PER_ROW == row => {
+            TileAssetData::DEPTH - TileAssetData::WIDTH
+        }
+        _ => 0.0,
+    };
+
+    let is_sideways = sideways == Some(index);
+    let width = if is_sideways {
+        TileAssetData::DEPTH
+    } else {
+        TileAssetData::WIDTH
+    };
+
+    let x = column as f32 * TileAssetData::WIDTH + sideways_offset + width / 2.0 - half_row_length;
+
+    let y = TileAssetData::HEIGHT / 2.0;
+
+    let z = half_row_length + row as f32 * TileAssetData::DEPTH + TileAssetData::DEPTH / 2.0;
+
+    let rotation = if is_sideways {
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)
+    } else {
+        Quat::identity()
+    };
+
+    Transform {
+        translation: Vec3::new(x, y, z),
+        rotation,
+        scale: Vec3::one(),
+    }
+}
+
+pub fn calculate_hand_to_river_animation(
+    index: usize,
+    sideways: Option<usize>,
+    seat: Wind,
+    transform: Transform,
+) -> EasingChainComponent<Transform> {
+    let transform_to = Transform::from_rotation(calculate_rotation_from_seat(seat))
+        * calculate_river_transform_from_index(index, sideways);
+
+    let up_hand = Transform {
+        translation: transform.translation + Vec3::new(0.0, TileAssetData::HEIGHT * 2.0, 0.0),
+        ..transform
+    };
+
+    let up_river = Transform {
+        translation: transform_to.translation + Vec3::new(0.0, TileAssetData::HEIGHT * 2.0, 0.0),
+        ..transform_to
+    };
+
+    transform
+        .ease_to(
+            up_hand,
+            EaseMethod::Linear,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(200),
+            },
+        )
+        .ease_to(
+            up_river,
+            EaseFunction::QuadraticOut,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(500),
+            },
+        )
+        .ease_to(
+            transform_to,
+            EaseFunction::QuadraticOut,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(200),
+            },
+        )
+}
@@ -0,0 +1,398 @@
+use crate::player::{Player, PlayerIdent, Players};
+use crate::riichi::RiichiStick;
+use crate::rules::Rules;
+use crate::ryuukyoku::{AbortiveDraw, ExhaustiveDraw};
+use crate::scoring::payment;
+use crate::scoring::{self, DoraCounts, Evaluation, WinContext, WinMethod};
+use crate::tiles::counts::TileCounts;
+use crate::tiles::shanten;
+use crate::tiles::{EnumIter, Tile, Wind};
+use crate::turn::TurnPhase;
+use crate::wall::{Doras, RevealUraDora, TileEntity, Wall};
+use crate::GameState;
+use bevy::prelude::*;
+use bevy_easings::{EasingChainComponent, EasingComponent};
+
+const HANDS_PER_WIND: u8 = 4;
+const HAND_END_SECONDS: f32 = 3.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandResult {
+    Win { dealer: bool },
+    ExhaustiveDraw { dealer_tenpai: bool },
+    AbortiveDraw,
+}
+
+impl HandResult {
+    /// The dealer keeps the seat after winning, being tenpai at an exhaustive draw and after
+    /// an abortive draw.
+    pub fn is_renchan(self) -> bool {
+        match self {
+            Self::Win { dealer } => dealer,
+            Self::ExhaustiveDraw { dealer_tenpai } => dealer_tenpai,
+            Self::AbortiveDraw => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Round {
+    pub wind: Wind,
+    /// Number of the hand within the round wind starting at 1.
+    pub hand: u8,
+    pub honba: u8,
+    pub riichi_sticks: u8,
+    pub result: Option<HandResult>,
+}
+
+impl Default for Round {
+    fn default() -> Self {
+        Self {
+            wind: Wind::East,
+            hand: 1,
+            honba: 0,
+            riichi_sticks: 0,
+            result: None,
+        }
+    }
+}
+
+impl Round {
+    /// Only a win of another player than the dealer clears the honba.
+    pub fn finish_hand(&mut self, result: HandResult) {
+        self.honba = match result {
+            HandResult::Win { dealer: false } => 0,
+            _ => self.honba + 1,
+        };
+        self.result = Some(result);
+
+        info!(
+            "Hand finished with {:?}, renchan: {}, honba: {}.",
+            result,
+            result.is_renchan(),
+            self.honba
+        );
+    }
+
+    fn next_hand(&mut self, renchan: bool) {
+        if renchan {
+            return;
+        }
+
+        self.hand += 1;
+
+        if self.hand > HANDS_PER_WIND {
+            self.hand = 1;
+            self.wind = self.wind.next();
+        }
+    }
+
+    /// The match ends when someone busts and after all-last, unless the dealer repeats without
+    /// being on top or nobody reached the target points and the rules allow an extension. In
+    /// the extension the first hand which ends with someone at the target points is the last
+    /// one.
+    fn is_match_over(&self, renchan: bool, players: &Players, rules: &Rules) -> bool {
+        if rules.busting && players.iter().any(|player| player.points < 0) {
+            return true;
+        }
+
+        let is_last_hand = self.hand == HANDS_PER_WIND;
+        let reached_target = players
+            .iter()
+            .any(|player| player.points >= rules.target_points);
+
+        match (self.wind as usize).cmp(&(rules.length.last_round_wind() as usize)) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal if !is_last_hand => false,
+            std::cmp::Ordering::Equal if renchan => {
+                let dealer = players.dealer();
+                let is_dealer_top = players.iter().all(|player| player.points <= dealer.points);
+                is_dealer_top && (dealer.points >= rules.target_points || !rules.extension)
+            }
+            std::cmp::Ordering::Equal => reached_target || !rules.extension,
+            std::cmp::Ordering::Greater => reached_target || (is_last_hand && !renchan),
+        }
+    }
+}
+
+/// Keeps the result of the hand on the table for a moment before the next hand starts.
+pub struct HandEndDelay {
+    timer: Timer,
+}
+
+impl Default for HandEndDelay {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(HAND_END_SECONDS, false),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DeclareWin {
+    seat: Wind,
+    discarder: Option<Wind>,
+}
+
+impl DeclareWin {
+    pub fn tsumo(seat: Wind) -> Self {
+        Self {
+            seat,
+            discarder: None,
+        }
+    }
+
+    pub fn ron(seat: Wind, discarder: Wind) -> Self {
+        Self {
+            seat,
+            discarder: Some(discarder),
+        }
+    }
+}
+
+/// Evaluates the hand of the player as if it was won right now, either by the last drawn tile
+/// or by the given discard.
+pub fn evaluate_win(
+    player: &Player,
+    players: &Players,
+    wall: &Wall,
+    round: &Round,
+    doras: &Doras,
+    rules: &Rules,
+    discard: Option<TileEntity>,
+) -> Option<(Evaluation, WinContext)> {
+    let mut concealed = player.hand_tiles();
+
+    let (winning_tile, method) = match discard {
+        Some(TileEntity { tile, .. }) => {
+            concealed.push(tile);
+            (tile, WinMethod::Ron)
+        }
+        None => (*concealed.last()?, WinMethod::Tsumo),
+    };
+
+    let mut context = WinContext::new(winning_tile, method, player.wind, round.wind);
+    context.first_draw = player.river.is_empty() && !players.has_calls();
+    context.rinshan = method == WinMethod::Tsumo && player.is_rinshan();
+    // The replacement tile of a kan comes from the dead wall and is never the last tile.
+    context.last_tile = wall.is_exhausted() && !context.rinshan;
+    context.chankan = discard.map_or(false, |discard| {
+        players
+            .iter()
+            .filter_map(Player::added_kan_tile)
+            .any(|added| added.entity == discard.entity)
+    });
+
+    if let Some(riichi) = player.riichi {
+        context.riichi = true;
+        context.double_riichi = riichi.double;
+        context.ippatsu = riichi.ippatsu && rules.ippatsu;
+    }
+
+    let all_tiles = player
+        .melds
+        .iter()
+        .flat_map(|meld| meld.tiles.iter().map(|tile_entity| tile_entity.tile))
+        .chain(concealed.iter().copied())
+        .collect::<Vec<_>>();
+
+    let red_fives = player
+        .melds
+        .iter()
+        .flat_map(|meld| meld.tiles.iter())
+        .chain(player.tiles.iter())
+        .chain(discard.iter())
+        .filter(|tile_entity| tile_entity.red)
+        .count();
+
+    context.dora = DoraCounts {
+        dora: doras.count(&all_tiles),
+        ura_dora: if context.riichi && rules.ura_dora {
+            doras.count_ura(&all_tiles)
+        } else {
+            0
+        },
+        red_fives: red_fives as u8,
+    };
+
+    let melds = player.meld_sets();
+    let evaluation = scoring::evaluate(&concealed, &melds, &context, rules)?;
+
+    // Without atozuke every tile of the wait has to give a yaku, not only the winning one.
+    if !rules.atozuke {
+        let waiting = &concealed[..concealed.len() - 1];
+        let counts = TileCounts::from(waiting);
+
+        let has_yaku_on_every_wait = shanten::winning_tiles(&counts, melds.len())
+            .into_iter()
+            .all(|tile| {
+                let mut tiles = waiting.to_vec();
+                tiles.push(tile);
+
+                let context = WinContext {
+                    winning_tile: tile,
+                    ..context
+                };
+                scoring::evaluate(&tiles, &melds, &context, rules).is_some()
+            });
+
+        if !has_yaku_on_every_wait {
+            return None;
+        }
+    }
+
+    Some((evaluation, context))
+}
+
+pub fn settle_win_system(
+    commands: &mut Commands,
+    mut players: ResMut<Players>,
+    mut round: ResMut<Round>,
+    doras: Res<Doras>,
+    wall: Res<Wall>,
+    rules: Res<Rules>,
+    events: Query<(Entity, &DeclareWin)>,
+    riichi_sticks: Query<Entity, With<RiichiStick>>,
+) {
+    // On multiple ron the winners are paid in turn order, so the first one takes the riichi
+    // sticks.
+    let mut wins = events
+        .iter()
+        .map(|(event, &win)| (event, win))
+        .collect::<Vec<_>>();
+    wins.sort_by_key(|(_, win)| {
+        win.discarder.map_or(0, |discarder| {
+            (4 + win.seat as usize - discarder as usize) % 4
+        })
+    });
+
+    let mut dealer_won = None;
+    let mut reveal_ura_dora = false;
+
+    for (event, DeclareWin { seat, discarder }) in wins {
+        let discard = discarder.and_then(|discarder| {
+            players
+                .player_by_ident(PlayerIdent::Seat(discarder))
+                .claimable_tile()
+        });
+
+        let winner = players.player_by_ident(PlayerIdent::Seat(seat));
+
+        match evaluate_win(winner, &players, &wall, &round, &doras, &rules, discard) {
+            Some((evaluation, context)) => {
+                let score = if rules.kiriage_mangan {
+                    evaluation.score(&context).kiriage()
+                } else {
+                    evaluation.score(&context)
+                };
+                let discarder_wind = discarder
+                    .map(|discarder| players.player_by_ident(PlayerIdent::Seat(discarder)).wind);
+
+                let deltas = payment::payments(
+                    &score,
+                    winner.wind,
+                    discarder_wind,
+                    round.honba,
+                    round.riichi_sticks,
+                );
+
+                info!(
+                    "Player sitting {:?} won by {:?} with {} {:?} ({:?}).",
+                    seat,
+                    context.method,
+                    winner.notation(),
+                    evaluation.yaku,
+                    score
+                );
+
+                reveal_ura_dora |= context.riichi && rules.ura_dora;
+                dealer_won = Some(dealer_won.unwrap_or(false) || context.is_dealer());
+
+                for wind in Wind::iter() {
+                    players.player_by_ident_mut(PlayerIdent::Wind(wind)).points +=
+                        deltas[wind as usize];
+                }
+
+                round.riichi_sticks = 0;
+            }
+            None => {
+                error!("Player sitting {:?} declared an invalid win!", seat);
+            }
+        }
+
+        commands.despawn(event);
+    }
+
+    if let Some(dealer) = dealer_won {
+        round.finish_hand(HandResult::Win { dealer });
+
+        if reveal_ura_dora {
+            commands.spawn((RevealUraDora,));
+        }
+
+        for stick in riichi_sticks.iter() {
+            commands.despawn(stick);
+        }
+    }
+}
+
+pub fn start_hand_end_system(mut delay: ResMut<HandEndDelay>) {
+    *delay = HandEndDelay::default();
+}
+
+pub fn hand_end_system(
+    commands: &mut Commands,
+    time: Res<Time>,
+    mut delay: ResMut<HandEndDelay>,
+    mut round: ResMut<Round>,
+    mut players: ResMut<Players>,
+    rules: Res<Rules>,
+    mut game_state: ResMut<State<GameState>>,
+    mut turn_phase: ResMut<State<TurnPhase>>,
+    win_events: Query<Entity, With<DeclareWin>>,
+    exhaustive_draw_events: Query<Entity, With<ExhaustiveDraw>>,
+    abortive_draw_events: Query<Entity, With<AbortiveDraw>>,
+    easings: Query<Entity, With<EasingComponent<Transform>>>,
+    easing_chains: Query<Entity, With<EasingChainComponent<Transform>>>,
+    tiles: Query<Entity, With<Tile>>,
+) {
+    let is_busy = win_events.iter().next().is_some()
+        || exhaustive_draw_events.iter().next().is_some()
+        || abortive_draw_events.iter().next().is_some()
+        || easings.iter().next().is_some()
+        || easing_chains.iter().next().is_some();
+
+    if is_busy || !delay.timer.tick(time.delta_seconds()).finished() {
+        return;
+    }
+
+    let result = round.result.take().unwrap_or_else(|| {
+        warn!("The hand ended without a result, it is repeated.");
+        HandResult::AbortiveDraw
+    });
+    let renchan = result.is_renchan();
+
+    if round.is_match_over(renchan, &players, &rules) {
+        info!("The match is over.");
+        game_state.set_next(GameState::End).unwrap();
+        turn_phase.set_next(TurnPhase::Idle).unwrap();
+        return;
+    }
+
+    round.next_hand(renchan);
+    if !renchan {
+        players.rotate_dealer();
+    }
+
+    info!(
+        "Starting {:?} {} with {} honba and {} riichi sticks.",
+        round.wind, round.hand, round.honba, round.riichi_sticks
+    );
+
+    for tile in tiles.iter() {
+        commands.despawn(tile);
+    }
+    players.start_hand();
+
+    turn_phase.set_next(TurnPhase::Setup).unwrap();
+}
@@ -0,0 +1,264 @@
+use crate::claim::ClaimWindow;
+use crate::player::Players;
+use crate::rng::GameRng;
+use crate::ryuukyoku::AbortiveDrawRules;
+use crate::tiles::{TileGroup, Wind, TILE_GROUPS};
+use crate::GameState;
+use bevy::prelude::*;
+use preset::{Preset, PRESETS};
+use serde::Deserialize;
+
+pub mod preset;
+
+pub const RULES_FILE: &str = "rules.ron";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum MatchLength {
+    /// East round only.
+    Tonpuu,
+    /// East and south round.
+    Hanchan,
+}
+
+impl MatchLength {
+    pub fn last_round_wind(self) -> Wind {
+        match self {
+            Self::Tonpuu => Wind::East,
+            Self::Hanchan => Wind::South,
+        }
+    }
+}
+
+/// How many players can win on the same discard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum MultipleRon {
+    /// Atamahane, only the first player in turn order wins.
+    HeadBump,
+    Double,
+    Triple,
+}
+
+impl MultipleRon {
+    pub fn max_winners(self) -> usize {
+        match self {
+            Self::HeadBump => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+        }
+    }
+}
+
+/// When the indicator of an open or added kan is flipped, a concealed kan always flips it
+/// immediately.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum KanDora {
+    Immediate,
+    AfterDiscard,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum LocalYaku {
+    /// Ron before the first own draw without any calls, worth five han.
+    Renhou,
+    /// Seven pairs of two to eight of dots, counted as yakuman.
+    Daisharin,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub length: MatchLength,
+    pub starting_points: i32,
+    /// Points needed to end the match after all-last.
+    pub target_points: i32,
+    /// Continues into the next round wind while nobody reached the target points after
+    /// all-last, otherwise the match always ends there.
+    pub extension: bool,
+    /// Points every player is measured against at the end, the difference to the starting
+    /// points is the oka.
+    pub return_points: i32,
+    /// Bonus of each placement in thousands, e.g. `[15, 5, -5, -15]`.
+    pub uma: [i32; 4],
+    /// The match ends as soon as someone drops below zero.
+    pub busting: bool,
+    /// Riichi sticks left on the table at the end of the match go to first place instead of
+    /// being lost.
+    pub riichi_sticks_to_first: bool,
+    /// Red fives among the characters, dots and bamboos.
+    pub red_fives: [u8; 3],
+    pub ippatsu: bool,
+    pub ura_dora: bool,
+    /// Rounds 4 han 30 fu and 3 han 60 fu up to mangan.
+    pub kiriage_mangan: bool,
+    pub open_tanyao: bool,
+    /// Allows winning on a tile of the wait which gives a yaku while another one does not.
+    pub atozuke: bool,
+    pub multiple_ron: MultipleRon,
+    pub kan_dora: KanDora,
+    pub nagashi_mangan: bool,
+    pub local_yaku: Vec<LocalYaku>,
+    pub abortive_draws: AbortiveDrawRules,
+    /// Seconds the human player has to claim a discard.
+    pub claim_timeout: f32,
+    /// Order of the tile groups when sorting a hand.
+    pub tile_order: [TileGroup; 6],
+    /// Seed for reproducible matches, a random one is drawn if left out.
+    pub seed: Option<u64>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            length: MatchLength::Hanchan,
+            starting_points: 25_000,
+            target_points: 30_000,
+            extension: true,
+            return_points: 30_000,
+            uma: [15, 5, -5, -15],
+            busting: true,
+            riichi_sticks_to_first: true,
+            red_fives: [1, 1, 1],
+            ippatsu: true,
+            ura_dora: true,
+            kiriage_mangan: false,
+            open_tanyao: true,
+            atozuke: true,
+            multiple_ron: MultipleRon::Double,
+            kan_dora: KanDora::AfterDiscard,
+            nagashi_mangan: true,
+            local_yaku: Vec::new(),
+            abortive_draws: AbortiveDrawRules::default(),
+            claim_timeout: 5.0,
+            tile_order: TILE_GROUPS,
+            seed: None,
+        }
+    }
+}
+
+impl Rules {
+    /// Reads the rules from a RON file, missing options and a missing file fall back to the
+    /// defaults.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let rules: Self = match std::fs::read_to_string(path) {
+            Ok(content) => ron::from_str(&content)
+                .map_err(|err| format!("Could not parse rules {} because of {}!", path, err))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                return Err(format!("Could not read rules {} because of {}!", path, err));
+            }
+        };
+
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.uma.iter().sum::<i32>() != 0 {
+            return Err(format!("The uma {:?} does not sum up to zero!", self.uma));
+        }
+
+        if self.return_points < self.starting_points {
+            return Err(format!(
+                "The return points {} are below the starting points {}!",
+                self.return_points, self.starting_points
+            ));
+        }
+
+        if self.red_fives.iter().any(|&count| count > 4) {
+            return Err(format!(
+                "There are only four fives to make {:?} red fives!",
+                self.red_fives
+            ));
+        }
+
+        if !(self.claim_timeout > 0.0 && self.claim_timeout.is_finite()) {
+            return Err(format!(
+                "A claim timeout of {} seconds is not possible!",
+                self.claim_timeout
+            ));
+        }
+
+        if let Some(group) = TILE_GROUPS
+            .iter()
+            .find(|group| !self.tile_order.contains(group))
+        {
+            return Err(format!("The tile order lacks {:?}!", group));
+        }
+
+        Ok(())
+    }
+
+    /// Collected from every player and given to first place.
+    pub fn oka(&self) -> i32 {
+        (self.return_points - self.starting_points) * 4
+    }
+
+    pub fn has_local_yaku(&self, yaku: LocalYaku) -> bool {
+        self.local_yaku.contains(&yaku)
+    }
+}
+
+/// The rules picked on the setup screen, either one of the presets or the rules file.
+pub struct RulesChoice {
+    custom: Rules,
+    pub preset: Option<Preset>,
+}
+
+impl RulesChoice {
+    pub fn new(custom: Rules, preset: Option<Preset>) -> Self {
+        Self { custom, preset }
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.preset
+            .map_or_else(|| self.custom.clone(), Preset::rules)
+    }
+
+    /// The rules file comes first and is followed by the presets.
+    pub fn options() -> impl Iterator<Item = Option<Preset>> {
+        std::iter::once(None).chain(PRESETS.iter().copied().map(Some))
+    }
+
+    pub fn select_next(&mut self) {
+        let preset = self.preset;
+        self.preset = Self::options()
+            .skip_while(|&option| option != preset)
+            .nth(1)
+            .unwrap_or(None);
+    }
+
+    pub fn select_previous(&mut self) {
+        let preset = self.preset;
+        self.preset = Self::options()
+            .take_while(|&option| option != preset)
+            .last()
+            .unwrap_or_else(|| Self::options().last().unwrap());
+    }
+}
+
+/// Starts the match with the chosen rules, the players are seated anew as the starting points
+/// may have changed.
+pub fn confirm_rules_system(
+    keyboard: Res<Input<KeyCode>>,
+    choice: Res<RulesChoice>,
+    mut rules: ResMut<Rules>,
+    mut players: ResMut<Players>,
+    mut claim_window: ResMut<ClaimWindow>,
+    mut rng: ResMut<GameRng>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    *rules = choice.rules();
+    *players = Players::new(&rules, &mut rng);
+    *claim_window = ClaimWindow::new(rules.claim_timeout);
+
+    info!(
+        "Playing with {} rules.",
+        choice.preset.map_or("custom", Preset::name)
+    );
+
+    state.set_next(GameState::Play).unwrap();
+}
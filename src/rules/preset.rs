@@ -0,0 +1,110 @@
+use super::{KanDora, MatchLength, MultipleRon, Rules};
+use crate::ryuukyoku::AbortiveDrawRules;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Preset {
+    /// World Riichi Championship.
+    Wrc,
+    /// European Mahjong Association.
+    Ema,
+    /// Japan Professional Mahjong League, A-rules.
+    Jpml,
+    Tenhou,
+    MLeague,
+}
+
+pub const PRESETS: [Preset; 5] = [
+    Preset::Wrc,
+    Preset::Ema,
+    Preset::Jpml,
+    Preset::Tenhou,
+    Preset::MLeague,
+];
+
+impl Preset {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wrc => "WRC",
+            Self::Ema => "EMA",
+            Self::Jpml => "JPML",
+            Self::Tenhou => "Tenhou",
+            Self::MLeague => "M-League",
+        }
+    }
+
+    pub fn rules(self) -> Rules {
+        let no_abortive_draws = AbortiveDrawRules {
+            nine_terminals: false,
+            four_winds: false,
+            four_riichi: false,
+            four_kans: false,
+            triple_ron: false,
+        };
+
+        // Tournament rules start with the return points, so there is no oka.
+        let tournament = Rules {
+            length: MatchLength::Hanchan,
+            starting_points: 30_000,
+            target_points: 30_000,
+            extension: false,
+            return_points: 30_000,
+            uma: [15, 5, -5, -15],
+            busting: false,
+            red_fives: [0, 0, 0],
+            multiple_ron: MultipleRon::HeadBump,
+            kan_dora: KanDora::Immediate,
+            nagashi_mangan: false,
+            abortive_draws: no_abortive_draws,
+            ..Rules::default()
+        };
+
+        match self {
+            Self::Wrc => Rules {
+                kiriage_mangan: true,
+                ..tournament
+            },
+            Self::Ema => tournament,
+            Self::Jpml => Rules {
+                ippatsu: false,
+                ura_dora: false,
+                abortive_draws: AbortiveDrawRules {
+                    nine_terminals: true,
+                    ..no_abortive_draws
+                },
+                ..tournament
+            },
+            Self::Tenhou => Rules {
+                uma: [20, 10, -10, -20],
+                ..Rules::default()
+            },
+            Self::MLeague => Rules {
+                extension: false,
+                uma: [30, 10, -10, -30],
+                busting: false,
+                kiriage_mangan: true,
+                multiple_ron: MultipleRon::HeadBump,
+                ..Rules::default()
+            },
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        PRESETS
+            .iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name))
+            .copied()
+            .ok_or_else(|| {
+                let names = PRESETS
+                    .iter()
+                    .map(|preset| preset.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Unknown preset {}, choose one of {}!", name, names)
+            })
+    }
+}
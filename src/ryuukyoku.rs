@@ -0,0 +1,248 @@
+use crate::player::{calculate_rotation_from_seat, Player, PlayerIdent, Players};
+use crate::round::{HandResult, Round};
+use crate::rules::Rules;
+use crate::scoring::payment::{self, Score};
+use crate::tiles::{Honor, Tile, TileAssetData, Wind};
+use crate::wall::calculate_tile_flip_transforms;
+use bevy::prelude::*;
+use bevy_easings::{Ease, EaseFunction, EasingChainComponent, EasingType};
+use serde::Deserialize;
+
+/// Kinds of terminals and honors needed to abort with kyuushu kyuuhai.
+const NINE_TERMINALS: usize = 9;
+
+/// The wall ran out without anyone winning.
+pub struct ExhaustiveDraw;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AbortiveDrawKind {
+    /// Kyuushu kyuuhai, nine different terminals and honors on the first draw.
+    NineTerminals,
+    /// Suufon renda, everyone discards the same wind on the first turn.
+    FourWinds,
+    /// Suucha riichi, all four players declared riichi.
+    FourRiichi,
+    /// Suukaikan, four kans by more than one player.
+    FourKans,
+    /// Sanchahou, three players win on the same discard.
+    TripleRon,
+}
+
+/// Which abortive draws end the hand, the others are ignored.
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(default)]
+pub struct AbortiveDrawRules {
+    pub nine_terminals: bool,
+    pub four_winds: bool,
+    pub four_riichi: bool,
+    pub four_kans: bool,
+    pub triple_ron: bool,
+}
+
+impl Default for AbortiveDrawRules {
+    fn default() -> Self {
+        Self {
+            nine_terminals: true,
+            four_winds: true,
+            four_riichi: true,
+            four_kans: true,
+            triple_ron: true,
+        }
+    }
+}
+
+impl AbortiveDrawRules {
+    pub fn allows(&self, kind: AbortiveDrawKind) -> bool {
+        match kind {
+            AbortiveDrawKind::NineTerminals => self.nine_terminals,
+            AbortiveDrawKind::FourWinds => self.four_winds,
+            AbortiveDrawKind::FourRiichi => self.four_riichi,
+            AbortiveDrawKind::FourKans => self.four_kans,
+            AbortiveDrawKind::TripleRon => self.triple_ron,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AbortiveDraw {
+    kind: AbortiveDrawKind,
+}
+
+impl AbortiveDraw {
+    pub fn new(kind: AbortiveDrawKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// Kyuushu kyuuhai can be declared on the first draw of the player as long as nobody called.
+pub fn can_declare_nine_terminals(player: &Player, players: &Players) -> bool {
+    if !player.river.is_empty() || players.has_calls() || !player.has_drawn() {
+        return false;
+    }
+
+    let mut kinds = player
+        .tiles
+        .iter()
+        .map(|tile_entity| tile_entity.tile)
+        .filter(|tile| tile.is_terminal() || tile.is_honor())
+        .collect::<Vec<_>>();
+    kinds.sort_by_key(|tile| tile.id());
+    kinds.dedup();
+
+    kinds.len() >= NINE_TERMINALS
+}
+
+/// Every player discarded the same wind as first discard without any call in between.
+pub fn is_four_winds(players: &Players) -> bool {
+    if players.has_calls() {
+        return false;
+    }
+
+    let mut first_discards = players.iter().map(|player| {
+        if player.river.len() == 1 {
+            player.river.last().map(|discard| discard.tile())
+        } else {
+            None
+        }
+    });
+
+    match first_discards.next() {
+        Some(Some(tile @ Tile::Honor(Honor::Wind(_)))) => {
+            first_discards.all(|discard| discard == Some(tile))
+        }
+        _ => false,
+    }
+}
+
+pub fn is_four_riichi(players: &Players) -> bool {
+    players.iter().all(|player| player.riichi.is_some())
+}
+
+pub fn abortive_draw_system(
+    commands: &mut Commands,
+    mut round: ResMut<Round>,
+    events: Query<(Entity, &AbortiveDraw)>,
+) {
+    for (event, &AbortiveDraw { kind }) in events.iter() {
+        info!("The hand ended in an abortive draw ({:?}).", kind);
+
+        round.finish_hand(HandResult::AbortiveDraw);
+
+        commands.despawn(event);
+    }
+}
+
+/// Every discard is a terminal or honor and none of them was called.
+fn is_nagashi_mangan(player: &Player) -> bool {
+    !player.river.is_empty()
+        && player.river.iter().all(|discard| {
+            let tile = discard.tile();
+            discard.called_by.is_none() && (tile.is_terminal() || tile.is_honor())
+        })
+}
+
+pub fn exhaustive_draw_system(
+    commands: &mut Commands,
+    mut players: ResMut<Players>,
+    mut round: ResMut<Round>,
+    rules: Res<Rules>,
+    events: Query<Entity, With<ExhaustiveDraw>>,
+    transform_query: Query<&Transform>,
+) {
+    for event in events.iter() {
+        let mut tenpai = [false; 4];
+        let mut nagashi = Vec::new();
+
+        for player in players.iter() {
+            let is_tenpai = !player.winning_tiles().is_empty();
+            tenpai[player.wind as usize] = is_tenpai;
+
+            info!(
+                "Player sitting {:?} is {} with {}.",
+                player.seat,
+                if is_tenpai { "tenpai" } else { "noten" },
+                player.notation()
+            );
+
+            if rules.nagashi_mangan && is_nagashi_mangan(player) {
+                nagashi.push(player.wind);
+            }
+
+            if is_tenpai {
+                for tile_entity in player.tiles.iter() {
+                    if let Ok(transform) = transform_query.get(tile_entity.entity) {
+                        let animation = calculate_hand_reveal_animation(*transform, player.seat);
+                        commands.insert_one(tile_entity.entity, animation);
+                    }
+                }
+            }
+        }
+
+        // Nagashi mangan is paid like a tsumo mangan and replaces the noten penalty.
+        let deltas = if nagashi.is_empty() {
+            payment::noten_payments(tenpai)
+        } else {
+            let mangan = Score::new(5, 0, 0);
+            nagashi.iter().fold([0; 4], |mut deltas, &wind| {
+                info!("Player with wind {:?} achieved nagashi mangan.", wind);
+
+                let payments = payment::payments(&mangan, wind, None, 0, 0);
+                for (delta, payment) in deltas.iter_mut().zip(payments.iter()) {
+                    *delta += payment;
+                }
+                deltas
+            })
+        };
+
+        for wind in Wind::iter() {
+            players.player_by_ident_mut(PlayerIdent::Wind(wind)).points += deltas[wind as usize];
+        }
+
+        round.finish_hand(HandResult::ExhaustiveDraw {
+            dealer_tenpai: tenpai[Wind::East as usize],
+        });
+
+        commands.despawn(event);
+    }
+}
+
+/// Lays the standing tile face down and flips it face up.
+fn calculate_hand_reveal_animation(
+    transform: Transform,
+    seat: Wind,
+) -> EasingChainComponent<Transform> {
+    let face_down = Transform {
+        translation: Vec3::new(
+            transform.translation.x,
+            TileAssetData::HEIGHT / 2.0,
+            transform.translation.z,
+        ),
+        rotation: calculate_rotation_from_seat(seat) * Quat::from_rotation_x(std::f32::consts::PI),
+        scale: Vec3::one(),
+    };
+
+    let [transform_middle, face_up] = calculate_tile_flip_transforms(face_down);
+
+    transform
+        .ease_to(
+            face_down,
+            EaseFunction::QuadraticOut,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(300),
+            },
+        )
+        .ease_to(
+            transform_middle,
+            EaseFunction::CircularOut,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(500),
+            },
+        )
+        .ease_to(
+            face_up,
+            EaseFunction::CircularIn,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(500),
+            },
+        )
+}
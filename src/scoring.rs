@@ -0,0 +1,176 @@
+use crate::hand::{self, Decomposition, Set, SetKind};
+use crate::rules::Rules;
+use crate::tiles::{Tile, Wind};
+
+pub mod fu;
+pub mod payment;
+pub mod yaku;
+
+use payment::Score;
+use yaku::YakuHan;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WinMethod {
+    Tsumo,
+    Ron,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DoraCounts {
+    pub dora: u8,
+    pub ura_dora: u8,
+    pub red_fives: u8,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct WinContext {
+    pub winning_tile: Tile,
+    pub method: WinMethod,
+    pub riichi: bool,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    pub seat_wind: Wind,
+    pub round_wind: Wind,
+    /// Won on the last tile of the live wall, haitei for tsumo and houtei for ron.
+    pub last_tile: bool,
+    pub rinshan: bool,
+    pub chankan: bool,
+    /// Won before the first own discard without any calls, tenhou and chiihou by tsumo and
+    /// renhou by ron.
+    pub first_draw: bool,
+    pub dora: DoraCounts,
+}
+
+impl WinContext {
+    pub fn new(winning_tile: Tile, method: WinMethod, seat_wind: Wind, round_wind: Wind) -> Self {
+        Self {
+            winning_tile,
+            method,
+            riichi: false,
+            double_riichi: false,
+            ippatsu: false,
+            seat_wind,
+            round_wind,
+            last_tile: false,
+            rinshan: false,
+            chankan: false,
+            first_draw: false,
+            dora: DoraCounts::default(),
+        }
+    }
+
+    pub fn is_dealer(&self) -> bool {
+        self.seat_wind == Wind::East
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Wait {
+    Ryanmen,
+    Kanchan,
+    Penchan,
+    Shanpon,
+    Tanki,
+}
+
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub decomposition: Decomposition,
+    pub wait: Wait,
+    pub yaku: Vec<YakuHan>,
+}
+
+impl Evaluation {
+    pub fn han(&self) -> u8 {
+        self.yaku.iter().map(|yaku| yaku.han).sum()
+    }
+
+    pub fn yakuman(&self) -> u8 {
+        self.yaku
+            .iter()
+            .filter(|yaku| yaku.yaku.is_yakuman())
+            .map(|yaku| yaku.han / yaku::YAKUMAN_HAN)
+            .sum()
+    }
+
+    pub fn score(&self, context: &WinContext) -> Score {
+        let yakuman = self.yakuman();
+
+        if yakuman > 0 {
+            Score::new(0, 0, yakuman)
+        } else {
+            Score::new(self.han(), fu::count(self, context), 0)
+        }
+    }
+}
+
+/// Lists every way the winning tile could have completed the decomposition.
+pub fn waits(decomposition: &Decomposition, winning_tile: Tile) -> Vec<Wait> {
+    let (pair, sets) = match decomposition {
+        Decomposition::Standard { pair, sets } => (*pair, sets),
+        _ => return vec![Wait::Tanki],
+    };
+
+    let mut waits = Vec::new();
+
+    if pair == winning_tile {
+        waits.push(Wait::Tanki);
+    }
+
+    for set in sets
+        .iter()
+        .filter(|set| !set.open && set.contains(winning_tile))
+    {
+        let wait = match set.kind {
+            SetKind::Triplet => Wait::Shanpon,
+            SetKind::Sequence => {
+                let tiles = set.tiles();
+                let (low, middle, high) = (tiles[0], tiles[1], tiles[2]);
+
+                if winning_tile == middle {
+                    Wait::Kanchan
+                } else if (winning_tile == low && high.is_terminal())
+                    || (winning_tile == high && low.is_terminal())
+                {
+                    Wait::Penchan
+                } else {
+                    Wait::Ryanmen
+                }
+            }
+            SetKind::Quad => continue,
+        };
+
+        if !waits.contains(&wait) {
+            waits.push(wait);
+        }
+    }
+
+    waits
+}
+
+/// Finds the interpretation of the hand with the highest value. Returns `None` if the tiles do
+/// not form a complete hand or the hand has no yaku.
+pub fn evaluate(
+    concealed: &[Tile],
+    melds: &[Set],
+    context: &WinContext,
+    rules: &Rules,
+) -> Option<Evaluation> {
+    hand::decompose(concealed, melds)
+        .into_iter()
+        .flat_map(|decomposition| {
+            waits(&decomposition, context.winning_tile)
+                .into_iter()
+                .map(move |wait| (decomposition.clone(), wait))
+        })
+        .map(|(decomposition, wait)| {
+            let yaku = yaku::evaluate(&decomposition, wait, context, rules);
+            Evaluation {
+                decomposition,
+                wait,
+                yaku,
+            }
+        })
+        .filter(|evaluation| !evaluation.yaku.is_empty())
+        .max_by_key(|evaluation| evaluation.score(context).base_points)
+}
@@ -0,0 +1,84 @@
+use super::{Evaluation, Wait, WinContext, WinMethod};
+use crate::hand::{Decomposition, SetKind};
+use crate::scoring::yaku::Yaku;
+use crate::tiles::{Honor, Tile};
+
+const BASE_FU: u8 = 20;
+const SEVEN_PAIRS_FU: u8 = 25;
+const OPEN_PINFU_FU: u8 = 30;
+
+/// Counts the fu of the hand rounded up to the next ten. Seven pairs always have 25 fu.
+pub fn count(evaluation: &Evaluation, context: &WinContext) -> u8 {
+    let (pair, sets) = match &evaluation.decomposition {
+        Decomposition::Standard { pair, sets } => (*pair, sets),
+        Decomposition::SevenPairs { .. } => return SEVEN_PAIRS_FU,
+        Decomposition::ThirteenOrphans { .. } => return round_up(BASE_FU + 10),
+    };
+
+    let closed = sets.iter().all(|set| !set.open);
+    let has_yaku = |yaku: Yaku| evaluation.yaku.iter().any(|other| other.yaku == yaku);
+
+    if has_yaku(Yaku::Pinfu) {
+        return match context.method {
+            WinMethod::Tsumo => BASE_FU,
+            WinMethod::Ron => BASE_FU + 10,
+        };
+    }
+
+    let mut fu = BASE_FU;
+
+    match context.method {
+        WinMethod::Tsumo => fu += 2,
+        WinMethod::Ron if closed => fu += 10,
+        WinMethod::Ron => {}
+    }
+
+    for set in sets {
+        let base = match set.kind {
+            SetKind::Sequence => continue,
+            SetKind::Triplet => 2,
+            SetKind::Quad => 8,
+        };
+
+        let completed_by_ron = context.method == WinMethod::Ron
+            && evaluation.wait == Wait::Shanpon
+            && set.kind == SetKind::Triplet
+            && set.tile == context.winning_tile;
+
+        let concealed = if set.open || completed_by_ron { 1 } else { 2 };
+        let terminal_or_honor = if is_terminal_or_honor(set.tile) { 2 } else { 1 };
+
+        fu += base * concealed * terminal_or_honor;
+    }
+
+    if pair == Tile::from(context.seat_wind) {
+        fu += 2;
+    }
+
+    if pair == Tile::from(context.round_wind) {
+        fu += 2;
+    }
+
+    if let Tile::Honor(Honor::Dragon(_)) = pair {
+        fu += 2;
+    }
+
+    match evaluation.wait {
+        Wait::Kanchan | Wait::Penchan | Wait::Tanki => fu += 2,
+        Wait::Ryanmen | Wait::Shanpon => {}
+    }
+
+    if fu == BASE_FU {
+        return OPEN_PINFU_FU;
+    }
+
+    round_up(fu)
+}
+
+fn round_up(fu: u8) -> u8 {
+    (fu + 9) / 10 * 10
+}
+
+fn is_terminal_or_honor(tile: Tile) -> bool {
+    tile.is_terminal() || tile.is_honor()
+}
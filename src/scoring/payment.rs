@@ -0,0 +1,142 @@
+use crate::tiles::Wind;
+
+pub const RIICHI_STICK: i32 = 1_000;
+pub const HONBA_STICK: i32 = 300;
+pub const NOTEN_PENALTY: i32 = 3_000;
+
+const MANGAN_BASE: u32 = 2_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Limit {
+    None,
+    Mangan,
+    Haneman,
+    Baiman,
+    Sanbaiman,
+    Yakuman(u8),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub han: u8,
+    pub fu: u8,
+    pub limit: Limit,
+    pub base_points: u32,
+}
+
+impl Score {
+    /// Hands with 13 or more han count as a single yakuman, real yakuman are passed separately
+    /// and may count multiple times.
+    pub fn new(han: u8, fu: u8, yakuman: u8) -> Self {
+        let limit = match (yakuman, han) {
+            (yakuman, _) if yakuman > 0 => Limit::Yakuman(yakuman),
+            (_, han) if han >= 13 => Limit::Yakuman(1),
+            (_, 11..=12) => Limit::Sanbaiman,
+            (_, 8..=10) => Limit::Baiman,
+            (_, 6..=7) => Limit::Haneman,
+            (_, 5) => Limit::Mangan,
+            (_, han) if fu as u32 * 2u32.pow(han as u32 + 2) >= MANGAN_BASE => Limit::Mangan,
+            _ => Limit::None,
+        };
+
+        let base_points = match limit {
+            Limit::None => fu as u32 * 2u32.pow(han as u32 + 2),
+            Limit::Mangan => MANGAN_BASE,
+            Limit::Haneman => MANGAN_BASE * 3 / 2,
+            Limit::Baiman => MANGAN_BASE * 2,
+            Limit::Sanbaiman => MANGAN_BASE * 3,
+            Limit::Yakuman(yakuman) => MANGAN_BASE * 4 * yakuman as u32,
+        };
+
+        Self {
+            han,
+            fu,
+            limit,
+            base_points,
+        }
+    }
+
+    /// Kiriage mangan, 4 han 30 fu and 3 han 60 fu are rounded up to mangan.
+    pub fn kiriage(self) -> Self {
+        let is_just_below_mangan =
+            self.limit == Limit::None && matches!((self.han, self.fu), (4, 30) | (3, 60));
+
+        if is_just_below_mangan {
+            Self {
+                limit: Limit::Mangan,
+                base_points: MANGAN_BASE,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// Point changes of every seat wind indexed by `Wind as usize`. The discarder pays alone on
+/// ron, on tsumo everyone pays with the dealer paying double. Honba go to the winner as well as
+/// all riichi sticks on the table.
+pub fn payments(
+    score: &Score,
+    winner: Wind,
+    discarder: Option<Wind>,
+    honba: u8,
+    riichi_sticks: u8,
+) -> [i32; 4] {
+    let mut deltas = [0; 4];
+    let base = score.base_points;
+    let honba = honba as i32;
+
+    match discarder {
+        Some(discarder) => {
+            let factor = if winner == Wind::East { 6 } else { 4 };
+            let amount = round_up(base * factor) + honba * HONBA_STICK;
+
+            deltas[discarder as usize] -= amount;
+            deltas[winner as usize] += amount;
+        }
+        None => {
+            for wind in Wind::iter().filter(|&wind| wind != winner) {
+                let factor = if winner == Wind::East || wind == Wind::East {
+                    2
+                } else {
+                    1
+                };
+                let amount = round_up(base * factor) + honba * HONBA_STICK / 3;
+
+                deltas[wind as usize] -= amount;
+                deltas[winner as usize] += amount;
+            }
+        }
+    }
+
+    deltas[winner as usize] += riichi_sticks as i32 * RIICHI_STICK;
+
+    deltas
+}
+
+/// Point changes of every seat wind after an exhaustive draw, the noten players split the
+/// penalty and the tenpai players share it.
+pub fn noten_payments(tenpai: [bool; 4]) -> [i32; 4] {
+    let tenpai_count = tenpai.iter().filter(|&&tenpai| tenpai).count() as i32;
+
+    if tenpai_count == 0 || tenpai_count == 4 {
+        return [0; 4];
+    }
+
+    let mut deltas = [0; 4];
+
+    for (delta, &tenpai) in deltas.iter_mut().zip(tenpai.iter()) {
+        *delta = if tenpai {
+            NOTEN_PENALTY / tenpai_count
+        } else {
+            -NOTEN_PENALTY / (4 - tenpai_count)
+        };
+    }
+
+    deltas
+}
+
+fn round_up(points: u32) -> i32 {
+    ((points + 99) / 100 * 100) as i32
+}
@@ -0,0 +1,807 @@
+use super::{Wait, WinContext, WinMethod};
+use crate::hand::{Decomposition, SetKind};
+use crate::rules::{LocalYaku, Rules};
+use crate::tiles::counts::TileCounts;
+use crate::tiles::{Dragon, Honor, Suit, Tile, TILE_KINDS};
+
+pub const YAKUMAN_HAN: u8 = 13;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Yaku {
+    Riichi,
+    DoubleRiichi,
+    Ippatsu,
+    MenzenTsumo,
+    Pinfu,
+    Iipeikou,
+    Tanyao,
+    Haitei,
+    Houtei,
+    Rinshan,
+    Chankan,
+    SeatWind,
+    RoundWind,
+    WhiteDragon,
+    GreenDragon,
+    RedDragon,
+    Chiitoitsu,
+    Chanta,
+    Ittsuu,
+    SanshokuDoujun,
+    SanshokuDoukou,
+    Sankantsu,
+    Toitoi,
+    Sanankou,
+    Shousangen,
+    Honroutou,
+    Honitsu,
+    Junchan,
+    Ryanpeikou,
+    Chinitsu,
+    Kokushi,
+    KokushiJuusanmen,
+    Suuankou,
+    SuuankouTanki,
+    Daisangen,
+    Shousuushii,
+    Daisuushii,
+    Tsuuiisou,
+    Chinroutou,
+    Ryuuiisou,
+    ChuurenPoutou,
+    JunseiChuurenPoutou,
+    Suukantsu,
+    Tenhou,
+    Chiihou,
+    Renhou,
+    Daisharin,
+    Dora,
+    UraDora,
+    AkaDora,
+}
+
+impl Yaku {
+    /// Han of the yaku for a closed or open hand, `0` if the yaku requires a closed hand.
+    /// Dora are counted separately as their value depends on the number of tiles.
+    pub fn han(self, closed: bool) -> u8 {
+        let (closed_han, open_han) = match self {
+            Self::Riichi | Self::Ippatsu | Self::MenzenTsumo | Self::Pinfu | Self::Iipeikou => {
+                (1, 0)
+            }
+            Self::Tanyao
+            | Self::Haitei
+            | Self::Houtei
+            | Self::Rinshan
+            | Self::Chankan
+            | Self::SeatWind
+            | Self::RoundWind
+            | Self::WhiteDragon
+            | Self::GreenDragon
+            | Self::RedDragon => (1, 1),
+            Self::DoubleRiichi | Self::Chiitoitsu => (2, 0),
+            Self::Renhou => (5, 0),
+            Self::Chanta | Self::Ittsuu | Self::SanshokuDoujun => (2, 1),
+            Self::SanshokuDoukou
+            | Self::Sankantsu
+            | Self::Toitoi
+            | Self::Sanankou
+            | Self::Shousangen
+            | Self::Honroutou => (2, 2),
+            Self::Honitsu | Self::Junchan => (3, 2),
+            Self::Ryanpeikou => (3, 0),
+            Self::Chinitsu => (6, 5),
+            Self::Kokushi
+            | Self::Suuankou
+            | Self::ChuurenPoutou
+            | Self::Tenhou
+            | Self::Chiihou
+            | Self::Daisharin => (YAKUMAN_HAN, 0),
+            Self::Daisangen
+            | Self::Shousuushii
+            | Self::Tsuuiisou
+            | Self::Chinroutou
+            | Self::Ryuuiisou
+            | Self::Suukantsu => (YAKUMAN_HAN, YAKUMAN_HAN),
+            Self::KokushiJuusanmen | Self::SuuankouTanki | Self::JunseiChuurenPoutou => {
+                (2 * YAKUMAN_HAN, 0)
+            }
+            Self::Daisuushii => (2 * YAKUMAN_HAN, 2 * YAKUMAN_HAN),
+            Self::Dora | Self::UraDora | Self::AkaDora => (0, 0),
+        };
+
+        if closed {
+            closed_han
+        } else {
+            open_han
+        }
+    }
+
+    pub fn is_yakuman(self) -> bool {
+        self.han(true) >= YAKUMAN_HAN
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct YakuHan {
+    pub yaku: Yaku,
+    pub han: u8,
+}
+
+/// Everything about a single interpretation of a winning hand the yaku are checked against.
+struct Hand<'a> {
+    decomposition: &'a Decomposition,
+    wait: Wait,
+    context: &'a WinContext,
+    rules: &'a Rules,
+    closed: bool,
+    counts: TileCounts,
+    /// Pair and sets as tile indices, pairs have two and quads four entries.
+    groups: Vec<Vec<usize>>,
+    sequences: Vec<usize>,
+    triplets: Vec<usize>,
+    quads: usize,
+    concealed_triplets: usize,
+}
+
+impl<'a> Hand<'a> {
+    fn new(
+        decomposition: &'a Decomposition,
+        wait: Wait,
+        context: &'a WinContext,
+        rules: &'a Rules,
+    ) -> Self {
+        let winning_index = context.winning_tile.index().unwrap();
+
+        let mut hand = Self {
+            decomposition,
+            wait,
+            context,
+            rules,
+            closed: true,
+            counts: TileCounts::new(),
+            groups: Vec::new(),
+            sequences: Vec::new(),
+            triplets: Vec::new(),
+            quads: 0,
+            concealed_triplets: 0,
+        };
+
+        match decomposition {
+            Decomposition::Standard { pair, sets } => {
+                hand.groups.push(vec![pair.index().unwrap(); 2]);
+
+                for set in sets {
+                    let index = set.tile.index().unwrap();
+                    hand.closed &= !set.open;
+
+                    match set.kind {
+                        SetKind::Sequence => hand.sequences.push(index),
+                        SetKind::Triplet | SetKind::Quad => {
+                            hand.triplets.push(index);
+
+                            let completed_by_ron = context.method == WinMethod::Ron
+                                && wait == Wait::Shanpon
+                                && set.kind == SetKind::Triplet
+                                && index == winning_index;
+
+                            if !set.open && !completed_by_ron {
+                                hand.concealed_triplets += 1;
+                            }
+                        }
+                    }
+
+                    if set.kind == SetKind::Quad {
+                        hand.quads += 1;
+                    }
+
+                    hand.groups.push(
+                        set.tiles()
+                            .into_iter()
+                            .map(|tile| tile.index().unwrap())
+                            .collect(),
+                    );
+                }
+            }
+            Decomposition::SevenPairs { pairs } => {
+                for pair in pairs {
+                    hand.groups.push(vec![pair.index().unwrap(); 2]);
+                }
+            }
+            Decomposition::ThirteenOrphans { pair } => {
+                let pair = pair.index().unwrap();
+                for index in (0..TILE_KINDS).filter(|&index| !is_simple(index)) {
+                    hand.groups
+                        .push(vec![index; if index == pair { 2 } else { 1 }]);
+                }
+            }
+        }
+
+        hand.counts = TileCounts::from(hand.tiles().map(tile).collect::<Vec<_>>().as_slice());
+
+        hand
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = usize> + '_ {
+        self.groups.iter().flatten().copied()
+    }
+
+    fn pair(&self) -> Option<usize> {
+        match self.decomposition {
+            Decomposition::Standard { pair, .. } => pair.index(),
+            _ => None,
+        }
+    }
+
+    fn is_yakuhai(&self, index: usize) -> bool {
+        is_dragon(index)
+            || Tile::from_index(index) == Some(Tile::from(self.context.seat_wind))
+            || Tile::from_index(index) == Some(Tile::from(self.context.round_wind))
+    }
+
+    fn suits(&self) -> Vec<usize> {
+        let mut suits = self
+            .tiles()
+            .filter(|&index| !is_honor(index))
+            .map(|index| index / 9)
+            .collect::<Vec<_>>();
+        suits.sort_unstable();
+        suits.dedup();
+        suits
+    }
+
+    fn has_honors(&self) -> bool {
+        self.tiles().any(is_honor)
+    }
+
+    fn dragon_triplets(&self) -> usize {
+        self.triplets
+            .iter()
+            .filter(|&&index| is_dragon(index))
+            .count()
+    }
+
+    fn wind_triplets(&self) -> usize {
+        self.triplets
+            .iter()
+            .filter(|&&index| is_wind(index))
+            .count()
+    }
+}
+
+/// Lists every yaku of the hand with its han. Yakuman replace all other yaku and dora are
+/// only added if the hand has at least one yaku.
+pub fn evaluate(
+    decomposition: &Decomposition,
+    wait: Wait,
+    context: &WinContext,
+    rules: &Rules,
+) -> Vec<YakuHan> {
+    let hand = Hand::new(decomposition, wait, context, rules);
+
+    let with_han = |yaku: Vec<Yaku>| {
+        yaku.into_iter()
+            .map(|yaku| YakuHan {
+                yaku,
+                han: yaku.han(hand.closed),
+            })
+            .filter(|yaku| yaku.han > 0)
+            .collect::<Vec<_>>()
+    };
+
+    let yakuman = with_han(find_yakuman(&hand));
+    if !yakuman.is_empty() {
+        return yakuman;
+    }
+
+    let mut yaku = with_han(find_yaku(&hand));
+    if yaku.is_empty() {
+        return yaku;
+    }
+
+    let dora = [
+        (Yaku::Dora, context.dora.dora),
+        (Yaku::UraDora, context.dora.ura_dora),
+        (Yaku::AkaDora, context.dora.red_fives),
+    ];
+
+    yaku.extend(
+        dora.iter()
+            .filter(|(_, han)| *han > 0)
+            .map(|&(yaku, han)| YakuHan { yaku, han }),
+    );
+
+    yaku
+}
+
+fn find_yakuman(hand: &Hand) -> Vec<Yaku> {
+    let context = hand.context;
+    let mut yaku = Vec::new();
+
+    if context.first_draw && context.method == WinMethod::Tsumo {
+        yaku.push(if context.is_dealer() {
+            Yaku::Tenhou
+        } else {
+            Yaku::Chiihou
+        });
+    }
+
+    if let Decomposition::SevenPairs { .. } = hand.decomposition {
+        // Seven pairs of two to eight of dots.
+        let is_daisharin = hand
+            .tiles()
+            .all(|index| matches!(tile(index), Tile::Suit(Suit::Dot(_))) && is_simple(index));
+
+        if is_daisharin && hand.rules.has_local_yaku(LocalYaku::Daisharin) {
+            yaku.push(Yaku::Daisharin);
+        }
+    }
+
+    if let Decomposition::ThirteenOrphans { pair } = hand.decomposition {
+        yaku.push(if *pair == context.winning_tile {
+            Yaku::KokushiJuusanmen
+        } else {
+            Yaku::Kokushi
+        });
+        return yaku;
+    }
+
+    if hand.concealed_triplets == 4 {
+        yaku.push(if hand.wait == Wait::Tanki {
+            Yaku::SuuankouTanki
+        } else {
+            Yaku::Suuankou
+        });
+    }
+
+    if hand.dragon_triplets() == 3 {
+        yaku.push(Yaku::Daisangen);
+    }
+
+    if hand.wind_triplets() == 4 {
+        yaku.push(Yaku::Daisuushii);
+    } else if hand.wind_triplets() == 3 && hand.pair().map_or(false, is_wind) {
+        yaku.push(Yaku::Shousuushii);
+    }
+
+    if hand.tiles().all(is_honor) {
+        yaku.push(Yaku::Tsuuiisou);
+    }
+
+    if hand.tiles().all(is_terminal) {
+        yaku.push(Yaku::Chinroutou);
+    }
+
+    if hand.tiles().all(|index| tile(index).is_green()) {
+        yaku.push(Yaku::Ryuuiisou);
+    }
+
+    if hand.quads == 4 {
+        yaku.push(Yaku::Suukantsu);
+    }
+
+    if hand.closed && !hand.has_honors() && hand.suits().len() == 1 {
+        let suit = hand.suits()[0] * 9;
+        let mut counts = hand.counts[suit..suit + 9].to_vec();
+        let base = [3, 1, 1, 1, 1, 1, 1, 1, 3];
+
+        if counts
+            .iter()
+            .zip(base.iter())
+            .all(|(count, base)| count >= base)
+        {
+            counts[context.winning_tile.index().unwrap() - suit] -= 1;
+
+            yaku.push(if counts == base {
+                Yaku::JunseiChuurenPoutou
+            } else {
+                Yaku::ChuurenPoutou
+            });
+        }
+    }
+
+    yaku
+}
+
+fn find_yaku(hand: &Hand) -> Vec<Yaku> {
+    let context = hand.context;
+    let mut yaku = Vec::new();
+
+    if context.double_riichi {
+        yaku.push(Yaku::DoubleRiichi);
+    } else if context.riichi {
+        yaku.push(Yaku::Riichi);
+    }
+
+    if context.ippatsu && (context.riichi || context.double_riichi) {
+        yaku.push(Yaku::Ippatsu);
+    }
+
+    if context.method == WinMethod::Tsumo {
+        yaku.push(Yaku::MenzenTsumo);
+    }
+
+    if context.first_draw
+        && context.method == WinMethod::Ron
+        && hand.rules.has_local_yaku(LocalYaku::Renhou)
+    {
+        yaku.push(Yaku::Renhou);
+    }
+
+    if context.last_tile {
+        yaku.push(match context.method {
+            WinMethod::Tsumo => Yaku::Haitei,
+            WinMethod::Ron => Yaku::Houtei,
+        });
+    }
+
+    if context.rinshan {
+        yaku.push(Yaku::Rinshan);
+    }
+
+    if context.chankan {
+        yaku.push(Yaku::Chankan);
+    }
+
+    if hand.tiles().all(is_simple) && (hand.closed || hand.rules.open_tanyao) {
+        yaku.push(Yaku::Tanyao);
+    }
+
+    let suits = hand.suits();
+    if suits.len() == 1 {
+        yaku.push(if hand.has_honors() {
+            Yaku::Honitsu
+        } else {
+            Yaku::Chinitsu
+        });
+    }
+
+    let all_terminals_or_honors = hand.tiles().all(|index| !is_simple(index));
+    if all_terminals_or_honors && hand.has_honors() && !suits.is_empty() {
+        yaku.push(Yaku::Honroutou);
+    }
+
+    if let Decomposition::SevenPairs { .. } = hand.decomposition {
+        yaku.push(Yaku::Chiitoitsu);
+        return yaku;
+    }
+
+    if hand.sequences.len() == 4
+        && hand.wait == Wait::Ryanmen
+        && !hand.pair().map_or(false, |pair| hand.is_yakuhai(pair))
+    {
+        yaku.push(Yaku::Pinfu);
+    }
+
+    let identical_sequences = {
+        let mut sequences = hand.sequences.clone();
+        sequences.sort_unstable();
+        let mut pairs = 0;
+        let mut i = 0;
+        while i + 1 < sequences.len() {
+            if sequences[i] == sequences[i + 1] {
+                pairs += 1;
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+        pairs
+    };
+
+    match identical_sequences {
+        1 => yaku.push(Yaku::Iipeikou),
+        2 => yaku.push(Yaku::Ryanpeikou),
+        _ => {}
+    }
+
+    for &index in hand.triplets.iter() {
+        match tile(index) {
+            Tile::Honor(Honor::Dragon(Dragon::White)) => yaku.push(Yaku::WhiteDragon),
+            Tile::Honor(Honor::Dragon(Dragon::Green)) => yaku.push(Yaku::GreenDragon),
+            Tile::Honor(Honor::Dragon(Dragon::Red)) => yaku.push(Yaku::RedDragon),
+            _ => {}
+        }
+
+        if Tile::from_index(index) == Some(Tile::from(context.seat_wind)) {
+            yaku.push(Yaku::SeatWind);
+        }
+
+        if Tile::from_index(index) == Some(Tile::from(context.round_wind)) {
+            yaku.push(Yaku::RoundWind);
+        }
+    }
+
+    let in_all_suits =
+        |sets: &[usize], number: usize| (0..3).all(|suit| sets.contains(&(suit * 9 + number)));
+
+    if (0..7).any(|number| in_all_suits(&hand.sequences, number)) {
+        yaku.push(Yaku::SanshokuDoujun);
+    }
+
+    if (0..9).any(|number| in_all_suits(&hand.triplets, number)) {
+        yaku.push(Yaku::SanshokuDoukou);
+    }
+
+    if (0..3).any(|suit| {
+        [0, 3, 6]
+            .iter()
+            .all(|number| hand.sequences.contains(&(suit * 9 + number)))
+    }) {
+        yaku.push(Yaku::Ittsuu);
+    }
+
+    if hand.triplets.len() == 4 {
+        yaku.push(Yaku::Toitoi);
+    }
+
+    if hand.concealed_triplets == 3 {
+        yaku.push(Yaku::Sanankou);
+    }
+
+    if hand.quads == 3 {
+        yaku.push(Yaku::Sankantsu);
+    }
+
+    if hand.dragon_triplets() == 2 && hand.pair().map_or(false, is_dragon) {
+        yaku.push(Yaku::Shousangen);
+    }
+
+    let outside = hand
+        .groups
+        .iter()
+        .all(|group| group.iter().any(|&index| !is_simple(index)));
+
+    if outside && !all_terminals_or_honors {
+        yaku.push(if hand.has_honors() {
+            Yaku::Chanta
+        } else {
+            Yaku::Junchan
+        });
+    }
+
+    yaku
+}
+
+fn tile(index: usize) -> Tile {
+    Tile::from_index(index).unwrap()
+}
+
+fn is_honor(index: usize) -> bool {
+    tile(index).is_honor()
+}
+
+fn is_wind(index: usize) -> bool {
+    tile(index).is_wind()
+}
+
+fn is_dragon(index: usize) -> bool {
+    tile(index).is_dragon()
+}
+
+fn is_terminal(index: usize) -> bool {
+    tile(index).is_terminal()
+}
+
+fn is_simple(index: usize) -> bool {
+    tile(index).is_simple()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand::Set;
+    use crate::scoring;
+    use crate::tiles::notation::Notation;
+    use crate::tiles::Wind;
+
+    struct Case {
+        /// Concealed tiles including the winning tile, followed by the melds.
+        hand: &'static str,
+        winning_tile: &'static str,
+        method: WinMethod,
+        seat_wind: Wind,
+        dora: u8,
+        /// Empty if the hand must not be scored at all.
+        expected: &'static [(Yaku, u8)],
+    }
+
+    const fn ron(hand: &'static str, winning_tile: &'static str) -> Case {
+        Case {
+            hand,
+            winning_tile,
+            method: WinMethod::Ron,
+            seat_wind: Wind::South,
+            dora: 0,
+            expected: &[],
+        }
+    }
+
+    fn parse(hand: &str) -> (Vec<Tile>, Vec<Set>) {
+        let notation = hand.parse::<Notation>().unwrap();
+        let concealed = notation.tiles.iter().map(|notated| notated.tile).collect();
+        let melds = notation
+            .melds
+            .iter()
+            .map(|meld| {
+                let tile = meld.tiles.iter().map(|notated| notated.tile).min().unwrap();
+                let kind = if meld.tiles.len() == 4 {
+                    SetKind::Quad
+                } else if meld.tiles.iter().all(|notated| notated.tile == tile) {
+                    SetKind::Triplet
+                } else {
+                    SetKind::Sequence
+                };
+                Set::new(kind, tile, meld.open)
+            })
+            .collect();
+
+        (concealed, melds)
+    }
+
+    fn check(cases: &[Case]) {
+        let rules = Rules::default();
+
+        for case in cases {
+            let (concealed, melds) = parse(case.hand);
+            let mut context = WinContext::new(
+                case.winning_tile.parse().unwrap(),
+                case.method,
+                case.seat_wind,
+                Wind::East,
+            );
+            context.dora.dora = case.dora;
+
+            let yaku = scoring::evaluate(&concealed, &melds, &context, &rules)
+                .map(|evaluation| evaluation.yaku)
+                .unwrap_or_default();
+
+            assert_eq!(yaku.len(), case.expected.len(), "{}: {:?}", case.hand, yaku);
+            for &(yaku_kind, han) in case.expected {
+                assert!(
+                    yaku.contains(&YakuHan {
+                        yaku: yaku_kind,
+                        han
+                    }),
+                    "{}: {:?}",
+                    case.hand,
+                    yaku
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn open_hands_lose_han() {
+        check(&[
+            Case {
+                expected: &[(Yaku::Chanta, 2)],
+                ..ron("123999m789p789s11z", "3m")
+            },
+            Case {
+                expected: &[(Yaku::Chanta, 1)],
+                ..ron("123999m789p11z[789s]", "3m")
+            },
+            Case {
+                expected: &[(Yaku::Junchan, 3)],
+                ..ron("123999m789p789s11p", "3m")
+            },
+            Case {
+                expected: &[(Yaku::Junchan, 2)],
+                ..ron("123999m789p11p[789s]", "3m")
+            },
+        ]);
+    }
+
+    #[test]
+    fn pinfu_needs_a_two_sided_wait() {
+        check(&[
+            Case {
+                expected: &[(Yaku::Pinfu, 1), (Yaku::Tanyao, 1)],
+                ..ron("23488m567p234678s", "2s")
+            },
+            Case {
+                expected: &[(Yaku::Tanyao, 1)],
+                ..ron("23488m567p234678s", "3s")
+            },
+            Case {
+                expected: &[(Yaku::Tanyao, 1)],
+                ..ron("23488m567p678s[234s]", "2m")
+            },
+        ]);
+    }
+
+    #[test]
+    fn nine_gates_is_pure_on_a_nine_sided_wait() {
+        check(&[
+            Case {
+                expected: &[(Yaku::JunseiChuurenPoutou, 2 * YAKUMAN_HAN)],
+                ..ron("11123455678999m", "5m")
+            },
+            Case {
+                expected: &[(Yaku::ChuurenPoutou, YAKUMAN_HAN)],
+                ..ron("11122345678999m", "9m")
+            },
+        ]);
+    }
+
+    #[test]
+    fn four_concealed_triplets_need_a_concealed_last_triplet() {
+        check(&[
+            Case {
+                expected: &[(Yaku::SuuankouTanki, 2 * YAKUMAN_HAN)],
+                ..ron("111m222p333777s55z", "5z")
+            },
+            Case {
+                method: WinMethod::Tsumo,
+                expected: &[(Yaku::Suuankou, YAKUMAN_HAN)],
+                ..ron("111m222p333777s55z", "7s")
+            },
+            Case {
+                expected: &[(Yaku::Toitoi, 2), (Yaku::Sanankou, 2)],
+                ..ron("111m222p333777s55z", "7s")
+            },
+        ]);
+    }
+
+    #[test]
+    fn picks_between_one_and_two_identical_sequences() {
+        check(&[
+            Case {
+                expected: &[(Yaku::Ryanpeikou, 3), (Yaku::Tanyao, 1)],
+                ..ron("223344m556677p88s", "8s")
+            },
+            Case {
+                expected: &[(Yaku::Iipeikou, 1), (Yaku::Pinfu, 1), (Yaku::Tanyao, 1)],
+                ..ron("223344m567p67888s", "8s")
+            },
+        ]);
+    }
+
+    #[test]
+    fn counts_a_double_wind_twice() {
+        check(&[
+            Case {
+                seat_wind: Wind::East,
+                expected: &[(Yaku::SeatWind, 1), (Yaku::RoundWind, 1)],
+                ..ron("23499m567p789s111z", "4m")
+            },
+            Case {
+                expected: &[(Yaku::RoundWind, 1)],
+                ..ron("23499m567p789s111z", "4m")
+            },
+        ]);
+    }
+
+    #[test]
+    fn counts_dora_only_with_a_yaku() {
+        check(&[
+            Case {
+                dora: 2,
+                ..ron("12399m456p234s[789s]", "3m")
+            },
+            Case {
+                dora: 2,
+                expected: &[(Yaku::Tanyao, 1), (Yaku::Dora, 2)],
+                ..ron("234m456p23488s[678s]", "4m")
+            },
+        ]);
+    }
+
+    #[test]
+    fn finds_dragons_and_orphans_by_tile() {
+        check(&[
+            Case {
+                expected: &[
+                    (Yaku::WhiteDragon, 1),
+                    (Yaku::GreenDragon, 1),
+                    (Yaku::Shousangen, 2),
+                ],
+                ..ron("234m567p55566677z", "4m")
+            },
+            Case {
+                expected: &[(Yaku::Kokushi, YAKUMAN_HAN)],
+                ..ron("19m19p19s12345677z", "1m")
+            },
+        ]);
+    }
+}
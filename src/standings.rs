@@ -0,0 +1,127 @@
+use crate::player::Players;
+use crate::rules::Rules;
+use crate::scoring::payment::RIICHI_STICK;
+use crate::tiles::Wind;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Standing {
+    pub placement: usize,
+    pub seat: Wind,
+    pub points: i32,
+    /// Result after return points, oka and uma.
+    pub score: i32,
+}
+
+/// Players are placed by points, ties go to the player who was seated closer to the first
+/// dealer. Depending on the rules first place takes the riichi sticks left on the table.
+pub fn final_standings(players: &Players, riichi_sticks: u8, rules: &Rules) -> Vec<Standing> {
+    let mut players = players.iter().collect::<Vec<_>>();
+    players.sort_by_key(|player| (-player.points, player.starting_wind as usize));
+
+    players
+        .into_iter()
+        .enumerate()
+        .map(|(placement, player)| {
+            let points = if placement == 0 && rules.riichi_sticks_to_first {
+                player.points + riichi_sticks as i32 * RIICHI_STICK
+            } else {
+                player.points
+            };
+
+            let oka = if placement == 0 { rules.oka() } else { 0 };
+            let score = points - rules.return_points + oka + rules.uma[placement] * 1_000;
+
+            Standing {
+                placement: placement + 1,
+                seat: player.seat,
+                points,
+                score,
+            }
+        })
+        .collect()
+}
+
+/// Formats points in thousands with one decimal and a sign, e.g. `+45.0` or `-12.3`.
+pub fn format_thousands(points: i32) -> String {
+    let sign = if points < 0 { '-' } else { '+' };
+    let points = points.abs();
+
+    format!("{}{}.{}", sign, points / 1_000, points % 1_000 / 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerIdent;
+    use crate::rng::GameRng;
+
+    /// Players with the given points in order of their starting wind, East first.
+    fn players(points: [i32; 4], rules: &Rules) -> Players {
+        let mut players = Players::new(rules, &mut GameRng::new(Some(0)));
+
+        for (wind, &points) in Wind::iter().zip(points.iter()) {
+            players.player_by_ident_mut(PlayerIdent::Wind(wind)).points = points;
+        }
+
+        players
+    }
+
+    fn seat_of(players: &Players, starting_wind: Wind) -> Wind {
+        players
+            .player_by_ident(PlayerIdent::Wind(starting_wind))
+            .seat
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_starting_seat() {
+        let rules = Rules::default();
+        let players = players([25_000, 30_000, 30_000, 15_000], &rules);
+        let standings = final_standings(&players, 0, &rules);
+
+        let seats = standings
+            .iter()
+            .map(|standing| standing.seat)
+            .collect::<Vec<_>>();
+        let expected = [Wind::South, Wind::West, Wind::East, Wind::North]
+            .iter()
+            .map(|&wind| seat_of(&players, wind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(seats, expected);
+    }
+
+    #[test]
+    fn adds_oka_and_uma() {
+        let rules = Rules::default();
+        let players = players([45_000, 25_000, 20_000, 10_000], &rules);
+        let scores = final_standings(&players, 0, &rules)
+            .iter()
+            .map(|standing| standing.score)
+            .collect::<Vec<_>>();
+
+        assert_eq!(scores, vec![50_000, 0, -15_000, -35_000]);
+        assert_eq!(scores.iter().sum::<i32>(), 0);
+    }
+
+    #[test]
+    fn leftover_riichi_sticks_go_to_first_place() {
+        let mut rules = Rules::default();
+        let players = players([44_000, 25_000, 20_000, 9_000], &rules);
+
+        let standings = final_standings(&players, 2, &rules);
+        assert_eq!(standings[0].points, 46_000);
+        assert_eq!(standings[0].score, 51_000);
+
+        rules.riichi_sticks_to_first = false;
+        let standings = final_standings(&players, 2, &rules);
+        assert_eq!(standings[0].points, 44_000);
+    }
+
+    #[test]
+    fn formats_points_in_thousands() {
+        assert_eq!(format_thousands(50_000), "+50.0");
+        assert_eq!(format_thousands(0), "+0.0");
+        assert_eq!(format_thousands(-15_300), "-15.3");
+        assert_eq!(format_thousands(12_345), "+12.3");
+    }
+}
@@ -2,6 +2,50 @@ use crate::GameState;
 use bevy::ecs::bevy_utils::HashMap;
 use bevy::prelude::*;
 use bevy::utils::AHashExt;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::atomic::{self, AtomicBool};
+
+pub mod counts;
+pub mod notation;
+pub mod shanten;
+
+pub const TILE_KINDS: usize = 34;
+/// Index of the first honor, the east wind.
+pub const HONOR_OFFSET: usize = 27;
+/// Indices of the ones, nines and honors, which make up thirteen orphans.
+pub const TERMINALS_AND_HONORS: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+/// Seasons and plants, which follow the other tiles in `Tile::id`.
+pub const BONUS_KINDS: usize = 8;
+
+/// Set by `--ascii` for terminals without a font for the Mahjong Tiles block.
+static ASCII_TILES: AtomicBool = AtomicBool::new(false);
+
+/// Makes `Display` of every tile fall back to ASCII.
+pub fn use_ascii_tiles(ascii: bool) {
+    ASCII_TILES.store(ascii, atomic::Ordering::Relaxed);
+}
+
+/// Groups of tiles which are kept together when sorting a hand.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum TileGroup {
+    Chars,
+    Dots,
+    Bamboos,
+    Winds,
+    Dragons,
+    Bonus,
+}
+
+pub const TILE_GROUPS: [TileGroup; 6] = [
+    TileGroup::Chars,
+    TileGroup::Dots,
+    TileGroup::Bamboos,
+    TileGroup::Winds,
+    TileGroup::Dragons,
+    TileGroup::Bonus,
+];
 
 pub trait EnumIter {
     fn next(self) -> Self;
@@ -16,9 +60,9 @@ pub enum Tile {
 
 impl Tile {
     pub fn new_set(with_bonus: bool) -> Vec<Self> {
-        let dots = Number::iter().map(|num| Suit::Dot(num));
-        let bamboos = Number::iter().map(|num| Suit::Bamboo(num));
-        let chars = Number::iter().map(|num| Suit::Char(num));
+        let dots = Number::iter().map(Suit::Dot);
+        let bamboos = Number::iter().map(Suit::Bamboo);
+        let chars = Number::iter().map(Suit::Char);
         let suits = dots.chain(bamboos).chain(chars).map(Tile::from);
 
         let winds = Wind::iter().map(Honor::from);
@@ -37,6 +81,208 @@ impl Tile {
 
         tiles
     }
+
+    /// Pairs every tile of the set with whether it is a red five, `red_fives` holds the number
+    /// of red fives of characters, dots and bamboos.
+    pub fn new_set_with_red_fives(red_fives: [u8; 3]) -> Vec<(Self, bool)> {
+        let mut red_fives = red_fives;
+
+        Self::new_set(false)
+            .into_iter()
+            .map(|tile| {
+                let suit = tile
+                    .index()
+                    .filter(|&index| index < 27 && index % 9 == 4)
+                    .map(|index| index / 9);
+
+                let red = match suit {
+                    Some(suit) if red_fives[suit] > 0 => {
+                        red_fives[suit] -= 1;
+                        true
+                    }
+                    _ => false,
+                };
+
+                (tile, red)
+            })
+            .collect()
+    }
+
+    /// Dense index of the tile in `0..TILE_KINDS`, ordered characters, dots, bamboos, winds
+    /// and dragons. Bonus tiles have no index as they never take part in a hand.
+    pub fn index(self) -> Option<usize> {
+        Some(self.id() as usize).filter(|&id| id < TILE_KINDS)
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        if index < TILE_KINDS {
+            Self::from_id(index as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Compact id of every tile, the tiles of a hand in the order of `index` are followed by
+    /// the seasons and plants.
+    pub fn id(self) -> u8 {
+        let id = match self {
+            Self::Suit(Suit::Char(number)) => number as usize,
+            Self::Suit(Suit::Dot(number)) => 9 + number as usize,
+            Self::Suit(Suit::Bamboo(number)) => 18 + number as usize,
+            Self::Honor(Honor::Wind(wind)) => 27 + wind as usize,
+            Self::Honor(Honor::Dragon(dragon)) => 31 + dragon as usize,
+            Self::Bonus(Bonus::Season(season)) => TILE_KINDS + season as usize,
+            Self::Bonus(Bonus::Plant(plant)) => TILE_KINDS + 4 + plant as usize,
+        };
+
+        id as u8
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        let id = id as usize;
+
+        let tile: Self = match id {
+            0..=8 => Suit::Char(Number::iter().nth(id)?).into(),
+            9..=17 => Suit::Dot(Number::iter().nth(id - 9)?).into(),
+            18..=26 => Suit::Bamboo(Number::iter().nth(id - 18)?).into(),
+            27..=30 => Wind::iter().nth(id - 27)?.into(),
+            31..=33 => Dragon::iter().nth(id - 31)?.into(),
+            _ => match id - TILE_KINDS {
+                bonus if bonus < 4 => Season::iter().nth(bonus)?.into(),
+                bonus if bonus < BONUS_KINDS => Plant::iter().nth(bonus - 4)?.into(),
+                _ => return None,
+            },
+        };
+
+        Some(tile)
+    }
+
+    pub fn group(self) -> TileGroup {
+        match self {
+            Self::Suit(Suit::Char(_)) => TileGroup::Chars,
+            Self::Suit(Suit::Dot(_)) => TileGroup::Dots,
+            Self::Suit(Suit::Bamboo(_)) => TileGroup::Bamboos,
+            Self::Honor(Honor::Wind(_)) => TileGroup::Winds,
+            Self::Honor(Honor::Dragon(_)) => TileGroup::Dragons,
+            Self::Bonus(_) => TileGroup::Bonus,
+        }
+    }
+
+    /// Sorts by the position of the group in `order` and within a group by the canonical order.
+    pub fn sort_key(self, order: &[TileGroup]) -> (usize, Self) {
+        let group = self.group();
+        let position = order.iter().position(|&other| other == group);
+
+        (position.unwrap_or(order.len()), self)
+    }
+
+    /// The next tile of a run, unlike `EnumIter::next` nine has no successor and neither have
+    /// honors and bonus tiles.
+    pub fn successor(self) -> Option<Self> {
+        match self {
+            Self::Suit(suit) => suit.successor().map(Self::from),
+            _ => None,
+        }
+    }
+
+    pub fn is_honor(self) -> bool {
+        matches!(self, Self::Honor(_))
+    }
+
+    pub fn is_wind(self) -> bool {
+        matches!(self, Self::Honor(Honor::Wind(_)))
+    }
+
+    pub fn is_dragon(self) -> bool {
+        matches!(self, Self::Honor(Honor::Dragon(_)))
+    }
+
+    /// One or nine of a suit.
+    pub fn is_terminal(self) -> bool {
+        match self {
+            Self::Suit(suit) => matches!(suit.number(), Number::One | Number::Nine),
+            _ => false,
+        }
+    }
+
+    /// Two to eight of a suit.
+    pub fn is_simple(self) -> bool {
+        matches!(self, Self::Suit(_)) && !self.is_terminal()
+    }
+
+    /// Tiles allowed in ryuuiisou, the two, three, four, six and eight of bamboos and the
+    /// green dragon.
+    pub fn is_green(self) -> bool {
+        match self {
+            Self::Suit(Suit::Bamboo(number)) => matches!(
+                number,
+                Number::Two | Number::Three | Number::Four | Number::Six | Number::Eight
+            ),
+            Self::Honor(Honor::Dragon(dragon)) => dragon == Dragon::Green,
+            _ => false,
+        }
+    }
+
+    /// The tile which counts as dora when this tile is the indicator. Numbers wrap from nine to
+    /// one, winds cycle east, south, west, north and dragons white, green, red.
+    pub fn dora_from_indicator(self) -> Self {
+        let index = match self.index() {
+            Some(index) => index,
+            None => return self,
+        };
+
+        let (start, length) = match index {
+            0..=26 => (index / 9 * 9, 9),
+            27..=30 => (27, 4),
+            _ => (31, 3),
+        };
+
+        Self::from_index(start + (index - start + 1) % length).unwrap()
+    }
+}
+
+/// Canonical order of characters, dots, bamboos, winds, dragons and bonus tiles.
+impl Ord for Tile {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id().cmp(&other.id())
+    }
+}
+
+impl PartialOrd for Tile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Shows the glyph of the Mahjong Tiles block. The alternate form `{:#}` and `--ascii` fall
+/// back to ASCII with the `123m456p789s1234567z` notation and the names of bonus tiles.
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() || ASCII_TILES.load(atomic::Ordering::Relaxed) {
+            return match self {
+                Self::Bonus(Bonus::Season(season)) => write!(f, "{:?}", season),
+                Self::Bonus(Bonus::Plant(plant)) => write!(f, "{:?}", plant),
+                tile => write!(f, "{}", notation::format(&[(*tile).into()])),
+            };
+        }
+
+        let code_point = match *self {
+            Self::Suit(Suit::Char(number)) => 0x1F007 + number as u32,
+            Self::Suit(Suit::Bamboo(number)) => 0x1F010 + number as u32,
+            Self::Suit(Suit::Dot(number)) => 0x1F019 + number as u32,
+            Self::Honor(Honor::Wind(wind)) => 0x1F000 + wind as u32,
+            Self::Honor(Honor::Dragon(dragon)) => 0x1F006 - dragon as u32,
+            Self::Bonus(Bonus::Plant(plant)) => match plant {
+                Plant::Plum => 0x1F022,
+                Plant::Orchid => 0x1F023,
+                Plant::Bamboo => 0x1F024,
+                Plant::Chrysanthemum => 0x1F025,
+            },
+            Self::Bonus(Bonus::Season(season)) => 0x1F026 + season as u32,
+        };
+
+        write!(f, "{}", std::char::from_u32(code_point).unwrap())
+    }
 }
 
 impl From<Suit> for Tile {
@@ -51,6 +297,18 @@ impl From<Honor> for Tile {
     }
 }
 
+impl From<Season> for Tile {
+    fn from(season: Season) -> Self {
+        Tile::Bonus(season.into())
+    }
+}
+
+impl From<Plant> for Tile {
+    fn from(plant: Plant) -> Self {
+        Tile::Bonus(plant.into())
+    }
+}
+
 impl From<Bonus> for Tile {
     fn from(bonus: Bonus) -> Self {
         Tile::Bonus(bonus)
@@ -86,6 +344,24 @@ pub enum Suit {
     Char(Number),
 }
 
+impl Suit {
+    pub fn number(self) -> Number {
+        match self {
+            Self::Dot(number) | Self::Bamboo(number) | Self::Char(number) => number,
+        }
+    }
+
+    pub fn successor(self) -> Option<Self> {
+        let number = self.number().successor()?;
+
+        Some(match self {
+            Self::Dot(_) => Self::Dot(number),
+            Self::Bamboo(_) => Self::Bamboo(number),
+            Self::Char(_) => Self::Char(number),
+        })
+    }
+}
+
 impl EnumIter for Suit {
     fn next(self) -> Self {
         match self {
@@ -125,6 +401,10 @@ impl Number {
         .iter()
         .copied()
     }
+
+    pub fn successor(self) -> Option<Self> {
+        Self::iter().skip_while(|&number| number != self).nth(1)
+    }
 }
 
 impl EnumIter for Number {
@@ -275,6 +555,7 @@ pub struct TileAssetData {
     mesh_texture: Handle<Texture>,
     covers: HashMap<Tile, Handle<Texture>>,
     textures: HashMap<Tile, Handle<Texture>>,
+    red_textures: HashMap<Tile, Handle<Texture>>,
 }
 
 impl TileAssetData {
@@ -286,8 +567,14 @@ impl TileAssetData {
         self.mesh.clone()
     }
 
-    pub fn get_texture(&self, tile: Tile) -> Handle<Texture> {
-        self.textures.get(&tile.into()).unwrap().clone()
+    pub fn get_texture(&self, tile: Tile, red: bool) -> Handle<Texture> {
+        let textures = if red {
+            &self.red_textures
+        } else {
+            &self.textures
+        };
+
+        textures.get(&tile).unwrap().clone()
     }
 }
 
@@ -356,6 +643,7 @@ pub fn load_tile_asset_data_system(commands: &mut Commands, asset_server: Res<As
         mesh_texture,
         covers,
         textures: HashMap::new(),
+        red_textures: HashMap::new(),
     });
 }
 
@@ -366,8 +654,7 @@ pub fn blend_tile_textures_system(
 ) {
     if tile_asset_data
         .covers
-        .iter()
-        .map(|(_, handle)| handle)
+        .values()
         .chain(std::iter::once(&tile_asset_data.mesh_texture))
         .any(|handle| textures.get(handle).is_none())
     {
@@ -388,7 +675,46 @@ pub fn blend_tile_textures_system(
         })
         .collect();
 
-    state.set_next(GameState::Play).unwrap();
+    let fives = [
+        Tile::Suit(Suit::Char(Number::Five)),
+        Tile::Suit(Suit::Dot(Number::Five)),
+        Tile::Suit(Suit::Bamboo(Number::Five)),
+    ];
+
+    tile_asset_data.red_textures = fives
+        .iter()
+        .map(|tile| {
+            let new_texture = {
+                let mesh_texture = textures.get(&tile_asset_data.mesh_texture).unwrap();
+                let tile_texture = textures.get(&tile_asset_data.covers[tile]).unwrap();
+                alpha_blend_textures(mesh_texture, &tint_red(tile_texture))
+            };
+
+            (*tile, textures.add(new_texture))
+        })
+        .collect();
+
+    state.set_next(GameState::Setup).unwrap();
+}
+
+/// Red fives share the cover of the normal five with its colors shifted to red.
+fn tint_red(texture: &Texture) -> Texture {
+    let mut texture = texture.clone();
+
+    const COLOR_CHANNELS: usize = 4;
+
+    for pixel in texture.data.chunks_mut(COLOR_CHANNELS) {
+        let brightness = pixel[..3]
+            .iter()
+            .map(|&color| color as u32)
+            .max()
+            .unwrap_or(0);
+        pixel[0] = brightness as u8;
+        pixel[1] = (pixel[1] as u32 * brightness / 4 / 255) as u8;
+        pixel[2] = (pixel[2] as u32 * brightness / 4 / 255) as u8;
+    }
+
+    texture
 }
 
 fn alpha_blend_textures(mesh_texture: &Texture, tile_texture: &Texture) -> Texture {
@@ -420,3 +746,90 @@ fn alpha_blend_textures(mesh_texture: &Texture, tile_texture: &Texture) -> Textu
 
     mesh_texture
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_ids() {
+        for id in 0..(TILE_KINDS + BONUS_KINDS) as u8 {
+            let tile = Tile::from_id(id).unwrap();
+            assert_eq!(tile.id(), id);
+        }
+
+        assert_eq!(Tile::from_id((TILE_KINDS + BONUS_KINDS) as u8), None);
+    }
+
+    #[test]
+    fn indices_stop_before_bonus_tiles() {
+        for index in 0..TILE_KINDS {
+            let tile = Tile::from_index(index).unwrap();
+            assert_eq!(tile.index(), Some(index));
+            assert_eq!(tile.id() as usize, index);
+        }
+
+        assert_eq!(Tile::from_index(TILE_KINDS), None);
+        assert_eq!(Tile::from_id(TILE_KINDS as u8).unwrap().index(), None);
+    }
+
+    #[test]
+    fn full_set_has_every_id() {
+        let mut ids = Tile::new_set(true)
+            .into_iter()
+            .map(Tile::id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        assert_eq!(ids.len(), TILE_KINDS + BONUS_KINDS);
+    }
+
+    #[test]
+    fn successors_stop_at_nine() {
+        assert_eq!(
+            Tile::from(Suit::Dot(Number::One)).successor(),
+            Some(Tile::from(Suit::Dot(Number::Two)))
+        );
+        assert_eq!(
+            Tile::from(Suit::Bamboo(Number::Eight)).successor(),
+            Some(Tile::from(Suit::Bamboo(Number::Nine)))
+        );
+        assert_eq!(Tile::from(Suit::Char(Number::Nine)).successor(), None);
+        assert_eq!(Tile::from(Wind::North).successor(), None);
+        assert_eq!(Tile::from(Dragon::Red).successor(), None);
+    }
+
+    #[test]
+    fn classifies_tiles() {
+        let terminals = (0..TILE_KINDS)
+            .filter(|&index| Tile::from_index(index).unwrap().is_terminal())
+            .collect::<Vec<_>>();
+        assert_eq!(terminals, vec![0, 8, 9, 17, 18, 26]);
+
+        let simples = (0..TILE_KINDS)
+            .filter(|&index| Tile::from_index(index).unwrap().is_simple())
+            .count();
+        assert_eq!(simples, 3 * 7);
+
+        let greens = (0..TILE_KINDS)
+            .filter_map(Tile::from_index)
+            .filter(|tile| tile.is_green())
+            .collect::<Vec<_>>();
+        let expected = [
+            Number::Two,
+            Number::Three,
+            Number::Four,
+            Number::Six,
+            Number::Eight,
+        ]
+        .iter()
+        .map(|&number| Tile::from(Suit::Bamboo(number)))
+        .chain(std::iter::once(Tile::from(Dragon::Green)))
+        .collect::<Vec<_>>();
+        assert_eq!(greens, expected);
+
+        let bonus = Tile::from_id(TILE_KINDS as u8).unwrap();
+        assert!(!bonus.is_terminal() && !bonus.is_simple() && !bonus.is_honor());
+    }
+}
@@ -0,0 +1,133 @@
+use super::{Tile, TILE_KINDS};
+use std::ops::Deref;
+
+const MAX_COPIES: u8 = 4;
+
+/// How often each of the `TILE_KINDS` tiles occurs, indexed by `Tile::index`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TileCounts([u8; TILE_KINDS]);
+
+impl TileCounts {
+    pub fn new() -> Self {
+        Self([0; TILE_KINDS])
+    }
+
+    pub fn add(&mut self, tile: Tile) -> Result<(), String> {
+        match tile.index() {
+            Some(index) if self.0[index] < MAX_COPIES => {
+                self.0[index] += 1;
+                Ok(())
+            }
+            Some(_) => Err(format!(
+                "There are only {} copies of {:?}!",
+                MAX_COPIES, tile
+            )),
+            None => Err(format!("Cannot count bonus tile {:?}!", tile)),
+        }
+    }
+
+    pub fn remove(&mut self, tile: Tile) -> Result<(), String> {
+        match tile.index() {
+            Some(index) if self.0[index] > 0 => {
+                self.0[index] -= 1;
+                Ok(())
+            }
+            _ => Err(format!("There is no {:?} to remove!", tile)),
+        }
+    }
+
+    /// Every tile which occurs at least once with its count, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (Tile, u8)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(index, &count)| (Tile::from_index(index).unwrap(), count))
+    }
+
+    pub fn tiles(&self) -> Vec<Tile> {
+        self.iter()
+            .flat_map(|(tile, count)| std::iter::repeat(tile).take(count as usize))
+            .collect()
+    }
+}
+
+impl Default for TileCounts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for TileCounts {
+    type Target = [u8; TILE_KINDS];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Bonus tiles are skipped as they never take part in a hand.
+impl From<&[Tile]> for TileCounts {
+    fn from(tiles: &[Tile]) -> Self {
+        let mut counts = Self::new();
+
+        for index in tiles.iter().filter_map(|tile| tile.index()) {
+            counts.0[index] += 1;
+        }
+
+        counts
+    }
+}
+
+impl From<TileCounts> for Vec<Tile> {
+    fn from(counts: TileCounts) -> Self {
+        counts.tiles()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiles::notation::tiles;
+
+    #[test]
+    fn round_trips_sorted_tiles() {
+        let sorted = tiles("1119m19p55s12367z");
+        let counts = TileCounts::from(sorted.as_slice());
+
+        assert_eq!(Vec::<Tile>::from(counts), sorted);
+        assert_eq!(
+            TileCounts::from(tiles("5s9m1z5s").as_slice()),
+            TileCounts::from(tiles("9m55s1z").as_slice())
+        );
+    }
+
+    #[test]
+    fn skips_bonus_tiles() {
+        let mut with_bonus = tiles("123m");
+        with_bonus.push(Tile::from_id(TILE_KINDS as u8).unwrap());
+
+        assert_eq!(
+            TileCounts::from(with_bonus.as_slice()).tiles(),
+            tiles("123m")
+        );
+    }
+
+    #[test]
+    fn limits_copies() {
+        let five = tiles("5m")[0];
+        let mut counts = TileCounts::from(tiles("555m").as_slice());
+
+        assert!(counts.add(five).is_ok());
+        assert!(counts.add(five).is_err());
+        assert!(counts
+            .add(Tile::from_id(TILE_KINDS as u8).unwrap())
+            .is_err());
+
+        for _ in 0..4 {
+            assert!(counts.remove(five).is_ok());
+        }
+        assert!(counts.remove(five).is_err());
+        assert_eq!(counts, TileCounts::new());
+    }
+}
@@ -0,0 +1,267 @@
+//! The `123m456p789s1234567z` notation. Numbers are followed by their suit, characters `m`,
+//! dots `p`, bamboos `s` and honors `z` in the order east, south, west, north, white, green
+//! and red. A `0` is a red five, open melds are written in brackets, e.g. `[555z]`, and
+//! concealed kans in parentheses, e.g. `(1111m)`.
+
+use super::{Tile, HONOR_OFFSET, TILE_KINDS};
+use std::fmt;
+use std::str::FromStr;
+
+const SUITS: [char; 4] = ['m', 'p', 's', 'z'];
+const RED_FIVE: u32 = 0;
+const FIVE: u32 = 5;
+
+/// A tile as written in the notation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Notated {
+    pub tile: Tile,
+    pub red: bool,
+}
+
+impl From<Tile> for Notated {
+    fn from(tile: Tile) -> Self {
+        Self { tile, red: false }
+    }
+}
+
+/// A called meld or a concealed kan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotatedMeld {
+    pub tiles: Vec<Notated>,
+    pub open: bool,
+}
+
+/// Concealed tiles followed by the melds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notation {
+    pub tiles: Vec<Notated>,
+    pub melds: Vec<NotatedMeld>,
+}
+
+impl FromStr for Notation {
+    type Err = String;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::default();
+        let mut numbers = Vec::new();
+        let mut meld: Option<NotatedMeld> = None;
+
+        for c in notation.chars().filter(|c| !c.is_whitespace()) {
+            match c {
+                '0'..='9' => numbers.push(c.to_digit(10).unwrap()),
+                '[' | '(' if meld.is_none() && numbers.is_empty() => {
+                    meld = Some(NotatedMeld {
+                        tiles: Vec::new(),
+                        open: c == '[',
+                    })
+                }
+                ']' | ')' if numbers.is_empty() => match meld.take() {
+                    Some(meld) if !meld.tiles.is_empty() && meld.open == (c == ']') => {
+                        if !meld.open && !is_kan(&meld.tiles) {
+                            return Err(format!(
+                                "({}) is not a concealed kan!",
+                                format(&meld.tiles)
+                            ));
+                        }
+
+                        result.melds.push(meld);
+                    }
+                    _ => return Err(format!("Unexpected {} in {}!", c, notation)),
+                },
+                _ if SUITS.contains(&c) && !numbers.is_empty() => {
+                    let tiles = numbers
+                        .drain(..)
+                        .map(|number| parse_tile(number, c))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    match meld.as_mut() {
+                        Some(meld) => meld.tiles.extend(tiles),
+                        None => result.tiles.extend(tiles),
+                    }
+                }
+                _ => return Err(format!("Unexpected {} in {}!", c, notation)),
+            }
+        }
+
+        if !numbers.is_empty() || meld.is_some() {
+            return Err(format!("{} is incomplete!", notation));
+        }
+
+        Ok(result)
+    }
+}
+
+impl fmt::Display for Notation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format(&self.tiles))?;
+
+        for meld in self.melds.iter() {
+            if meld.open {
+                write!(f, "[{}]", format(&meld.tiles))?;
+            } else {
+                write!(f, "({})", format(&meld.tiles))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_kan(tiles: &[Notated]) -> bool {
+    tiles.len() == 4 && tiles.iter().all(|notated| notated.tile == tiles[0].tile)
+}
+
+fn parse_tile(number: u32, suit: char) -> Result<Notated, String> {
+    let red = number == RED_FIVE && suit != 'z';
+    let number = if red { FIVE } else { number };
+
+    let offset = SUITS.iter().position(|&other| other == suit).unwrap() * 9;
+    let index = (offset + number as usize).wrapping_sub(1);
+
+    let is_valid = number > 0 && (suit != 'z' || index < TILE_KINDS);
+
+    match Tile::from_index(index).filter(|_| is_valid) {
+        Some(tile) => Ok(Notated { tile, red }),
+        None => Err(format!("{}{} is not a tile!", number, suit)),
+    }
+}
+
+/// Writes the tiles in their order, consecutive tiles of the same suit share the suit letter.
+/// Bonus tiles have no notation and are left out.
+pub fn format(tiles: &[Notated]) -> String {
+    let mut result = String::new();
+    let mut current_suit = None;
+
+    for notated in tiles {
+        let index = match notated.tile.index() {
+            Some(index) => index,
+            None => continue,
+        };
+
+        let suit = SUITS[index.min(HONOR_OFFSET) / 9];
+        let number = if notated.red {
+            RED_FIVE as usize
+        } else {
+            index - index.min(HONOR_OFFSET) / 9 * 9 + 1
+        };
+
+        if current_suit.map_or(false, |current| current != suit) {
+            result.push(current_suit.unwrap());
+        }
+
+        result.push_str(&number.to_string());
+        current_suit = Some(suit);
+    }
+
+    if let Some(suit) = current_suit {
+        result.push(suit);
+    }
+
+    result
+}
+
+impl FromStr for Tile {
+    type Err = String;
+
+    /// Parses a single tile like `5m`, a red five `0m` is a normal five.
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let parsed = notation.parse::<Notation>()?;
+
+        match (parsed.tiles.as_slice(), parsed.melds.is_empty()) {
+            ([notated], true) => Ok(notated.tile),
+            _ => Err(format!("{} is not a single tile!", notation)),
+        }
+    }
+}
+
+/// The tiles outside of melds, panics on invalid notation.
+#[cfg(test)]
+pub fn tiles(notation: &str) -> Vec<Tile> {
+    let notation = notation.parse::<Notation>().unwrap();
+    notation.tiles.iter().map(|notated| notated.tile).collect()
+}
+
+/// A single tile, panics on invalid notation.
+#[cfg(test)]
+pub fn tile(notation: &str) -> Tile {
+    notation.parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(notation: &str) -> String {
+        notation.parse::<Notation>().unwrap().to_string()
+    }
+
+    #[test]
+    fn round_trips_hands_and_melds() {
+        for &notation in [
+            "123m456p789s1234567z",
+            "19m19p19s1234567z",
+            "0m55p[055s]",
+            "123m11z[111z](2222p)[4444s]",
+        ]
+        .iter()
+        {
+            assert_eq!(round_trip(notation), notation);
+        }
+
+        assert_eq!(round_trip("1m 2m3m 4p"), "123m4p");
+    }
+
+    #[test]
+    fn parses_red_fives() {
+        let notation = "0m0p0s5m".parse::<Notation>().unwrap();
+        let expected = ["5m", "5p", "5s", "5m"]
+            .iter()
+            .zip([true, true, true, false].iter())
+            .map(|(tile, &red)| Notated {
+                tile: tile.parse().unwrap(),
+                red,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(notation.tiles, expected);
+    }
+
+    #[test]
+    fn tells_open_melds_from_concealed_kans() {
+        let notation = "11z[123m](5555p)".parse::<Notation>().unwrap();
+
+        assert_eq!(notation.tiles.len(), 2);
+        assert_eq!(
+            notation
+                .melds
+                .iter()
+                .map(|meld| (meld.tiles.len(), meld.open))
+                .collect::<Vec<_>>(),
+            vec![(3, true), (4, false)]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_notation() {
+        for &notation in [
+            "1", "1x", "m", "0z", "8z", "[123m", "123m]", "[]", "[123m)", "(1111m]", "(123m)",
+            "[1[23]m]", "1[23m]",
+        ]
+        .iter()
+        {
+            assert!(
+                notation.parse::<Notation>().is_err(),
+                "{} was accepted",
+                notation
+            );
+        }
+    }
+
+    #[test]
+    fn parses_single_tiles() {
+        assert_eq!("0m".parse::<Tile>(), "5m".parse::<Tile>());
+        assert!("7z".parse::<Tile>().is_ok());
+        assert!("55m".parse::<Tile>().is_err());
+        assert!("[5m]".parse::<Tile>().is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use super::{Tile, HONOR_OFFSET, TERMINALS_AND_HONORS, TILE_KINDS};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const MAX_SETS: i8 = 4;
+const MAX_COPIES: u8 = 4;
+
+/// Sets, partial sets and whether a pair was used as head for one way to split a suit.
+type Blocks = (u8, u8, bool);
+
+thread_local! {
+    static SUIT_TABLE: RefCell<HashMap<u32, Rc<Vec<Blocks>>>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ukeire {
+    pub tile: Tile,
+    pub unseen: u8,
+}
+
+/// Number of tiles missing to reach tenpai, `-1` meaning the hand is already complete.
+/// `melds` is the number of called sets which are not part of `counts`.
+pub fn shanten(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    let standard = standard_shanten(counts, melds);
+
+    if melds == 0 {
+        standard
+            .min(seven_pairs_shanten(counts))
+            .min(thirteen_orphans_shanten(counts))
+    } else {
+        standard
+    }
+}
+
+pub fn standard_shanten(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    let groups = [
+        suit_blocks(&counts[0..9], false),
+        suit_blocks(&counts[9..18], false),
+        suit_blocks(&counts[18..27], false),
+        suit_blocks(&counts[HONOR_OFFSET..TILE_KINDS], true),
+    ];
+
+    let mut combined: Vec<Blocks> = vec![(0, 0, false)];
+
+    for group in groups.iter() {
+        let mut next = Vec::with_capacity(combined.len() * group.len());
+
+        for &(sets, partials, head) in combined.iter() {
+            for &(group_sets, group_partials, group_head) in group.iter() {
+                if head && group_head {
+                    continue;
+                }
+
+                next.push((
+                    sets + group_sets,
+                    partials + group_partials,
+                    head || group_head,
+                ));
+            }
+        }
+
+        combined = prune(next);
+    }
+
+    combined
+        .into_iter()
+        .map(|(sets, partials, head)| {
+            let sets = sets as i8 + melds as i8;
+            let partials = (partials as i8).min(MAX_SETS - sets).max(0);
+            8 - 2 * sets - partials - head as i8
+        })
+        .min()
+        .unwrap()
+}
+
+pub fn seven_pairs_shanten(counts: &[u8; TILE_KINDS]) -> i8 {
+    let pairs = counts.iter().filter(|&&count| count >= 2).count() as i8;
+    let kinds = counts.iter().filter(|&&count| count >= 1).count() as i8;
+
+    6 - pairs + (7 - kinds).max(0)
+}
+
+pub fn thirteen_orphans_shanten(counts: &[u8; TILE_KINDS]) -> i8 {
+    let kinds = TERMINALS_AND_HONORS
+        .iter()
+        .filter(|&&index| counts[index] >= 1)
+        .count() as i8;
+    let has_pair = TERMINALS_AND_HONORS.iter().any(|&index| counts[index] >= 2);
+
+    13 - kinds - has_pair as i8
+}
+
+/// Every tile which lowers the shanten of the hand together with the number of copies which
+/// are neither in the hand nor in `visible`, e.g. discards, dora indicators and called sets.
+pub fn ukeire(counts: &[u8; TILE_KINDS], melds: usize, visible: &[u8; TILE_KINDS]) -> Vec<Ukeire> {
+    let current = shanten(counts, melds);
+    let mut counts = *counts;
+    let mut tiles = Vec::new();
+
+    for index in 0..TILE_KINDS {
+        if counts[index] >= MAX_COPIES {
+            continue;
+        }
+
+        counts[index] += 1;
+        let improves = shanten(&counts, melds) < current;
+        counts[index] -= 1;
+
+        if improves {
+            tiles.push(Ukeire {
+                tile: Tile::from_index(index).unwrap(),
+                unseen: MAX_COPIES.saturating_sub(counts[index] + visible[index]),
+            });
+        }
+    }
+
+    tiles
+}
+
+/// Tiles which complete the hand, empty if the hand is not tenpai. Tiles already held four
+/// times are skipped as they cannot be drawn anymore.
+pub fn winning_tiles(counts: &[u8; TILE_KINDS], melds: usize) -> Vec<Tile> {
+    let mut counts = *counts;
+    let mut tiles = Vec::new();
+
+    for index in 0..TILE_KINDS {
+        if counts[index] >= MAX_COPIES {
+            continue;
+        }
+
+        counts[index] += 1;
+        if shanten(&counts, melds) == -1 {
+            tiles.push(Tile::from_index(index).unwrap());
+        }
+        counts[index] -= 1;
+    }
+
+    tiles
+}
+
+/// Looks up the pareto optimal splits of a single suit, computing and caching them the first
+/// time a pattern is seen.
+fn suit_blocks(counts: &[u8], honors: bool) -> Rc<Vec<Blocks>> {
+    let key = counts.iter().fold(0, |key, &count| key * 5 + count as u32) | (honors as u32) << 31;
+
+    if let Some(blocks) = SUIT_TABLE.with(|table| table.borrow().get(&key).cloned()) {
+        return blocks;
+    }
+
+    let blocks = Rc::new(compute_suit_blocks(counts, honors));
+    SUIT_TABLE.with(|table| table.borrow_mut().insert(key, blocks.clone()));
+
+    blocks
+}
+
+fn compute_suit_blocks(counts: &[u8], honors: bool) -> Vec<Blocks> {
+    let index = match counts.iter().position(|&count| count > 0) {
+        Some(index) => index,
+        None => return vec![(0, 0, false)],
+    };
+
+    let mut counts = counts.to_vec();
+    let mut blocks = Vec::new();
+
+    let mut split = |counts: &mut Vec<u8>, taken: &[usize], sets: u8, partials: u8, head: bool| {
+        for &i in taken {
+            counts[i] -= 1;
+        }
+
+        for &(rest_sets, rest_partials, rest_head) in suit_blocks(counts, honors).iter() {
+            if !(head && rest_head) {
+                blocks.push((
+                    rest_sets + sets,
+                    rest_partials + partials,
+                    rest_head || head,
+                ));
+            }
+        }
+
+        for &i in taken {
+            counts[i] += 1;
+        }
+    };
+
+    let has_next = !honors && index + 1 < counts.len() && counts[index + 1] > 0;
+    let has_after_next = !honors && index + 2 < counts.len() && counts[index + 2] > 0;
+
+    if counts[index] >= 3 {
+        split(&mut counts, &[index, index, index], 1, 0, false);
+    }
+
+    if has_next && has_after_next {
+        split(&mut counts, &[index, index + 1, index + 2], 1, 0, false);
+    }
+
+    if counts[index] >= 2 {
+        split(&mut counts, &[index, index], 0, 0, true);
+        split(&mut counts, &[index, index], 0, 1, false);
+    }
+
+    if has_next {
+        split(&mut counts, &[index, index + 1], 0, 1, false);
+    }
+
+    if has_after_next {
+        split(&mut counts, &[index, index + 2], 0, 1, false);
+    }
+
+    split(&mut counts, &[index], 0, 0, false);
+
+    prune(blocks)
+}
+
+/// Drops every split for which another split has at least as many sets and partial sets.
+fn prune(mut blocks: Vec<Blocks>) -> Vec<Blocks> {
+    blocks.sort_unstable();
+    blocks.dedup();
+
+    blocks
+        .iter()
+        .copied()
+        .filter(|&(sets, partials, head)| {
+            !blocks
+                .iter()
+                .any(|&(other_sets, other_partials, other_head)| {
+                    other_head == head
+                        && other_sets >= sets
+                        && other_partials >= partials
+                        && (other_sets, other_partials) != (sets, partials)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiles::counts::TileCounts;
+    use crate::tiles::notation::tiles;
+
+    fn counts(notation: &str) -> TileCounts {
+        TileCounts::from(tiles(notation).as_slice())
+    }
+
+    #[test]
+    fn standard_shanten_of_known_hands() {
+        assert_eq!(standard_shanten(&counts("123m456p789s11122z"), 0), -1);
+        assert_eq!(standard_shanten(&counts("123m456p789s1122z"), 0), 0);
+        assert_eq!(standard_shanten(&counts("59m456p78s11z123m"), 0), 2);
+        assert_eq!(standard_shanten(&counts("456p789s11z"), 2), -1);
+        assert_eq!(standard_shanten(&counts("456p1z"), 3), 0);
+    }
+
+    #[test]
+    fn seven_pairs_shanten_counts_four_of_a_kind_once() {
+        assert_eq!(seven_pairs_shanten(&counts("1122m3344p5566s7z")), 0);
+        assert_eq!(seven_pairs_shanten(&counts("1111m2233p4455s6z")), 2);
+    }
+
+    #[test]
+    fn thirteen_orphans_shanten_of_known_hands() {
+        assert_eq!(thirteen_orphans_shanten(&counts("19m19p19s1234567z")), 0);
+        assert_eq!(thirteen_orphans_shanten(&counts("19m19p19s1234566z")), 0);
+        assert_eq!(thirteen_orphans_shanten(&counts("159m19p19s123456z")), 1);
+    }
+
+    #[test]
+    fn shanten_takes_the_best_shape() {
+        assert_eq!(shanten(&counts("19m19p19s1234567z"), 0), 0);
+        assert_eq!(shanten(&counts("1122m3344p5566s7z"), 0), 0);
+        assert_eq!(shanten(&counts("1122m3344p5566s7z"), 1), 2);
+    }
+
+    #[test]
+    fn winning_tiles_of_nine_gates() {
+        assert_eq!(
+            winning_tiles(&counts("1112345678999m"), 0),
+            tiles("123456789m")
+        );
+    }
+
+    #[test]
+    fn winning_tiles_of_shanpon_and_thirteen_orphans() {
+        assert_eq!(winning_tiles(&counts("123m456p789s1122z"), 0), tiles("12z"));
+        assert_eq!(
+            winning_tiles(&counts("19m19p19s1234567z"), 0),
+            tiles("19m19p19s1234567z")
+        );
+        assert!(winning_tiles(&counts("59m456p78s11z123m"), 0).is_empty());
+    }
+
+    #[test]
+    fn ukeire_subtracts_visible_tiles() {
+        let visible = counts("1z");
+        let ukeire = ukeire(&counts("123m456p789s1122z"), 0, &visible);
+
+        assert_eq!(
+            ukeire,
+            vec![
+                Ukeire {
+                    tile: "1z".parse().unwrap(),
+                    unseen: 1,
+                },
+                Ukeire {
+                    tile: "2z".parse().unwrap(),
+                    unseen: 2,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::claim::CallTile;
+use crate::kan::{DeclareKan, KanKind};
+use crate::player::{
+    calculate_hand_animation, DiscardTile, DrawTiles, PlayerIdent, PlayerType, Players, SortHand,
+};
+use crate::riichi::MIN_TILES_FOR_RIICHI;
+use crate::round::{self, DeclareWin, Round};
+use crate::rules::Rules;
+use crate::ryuukyoku::{self, AbortiveDraw, AbortiveDrawKind};
+use crate::wall::{Doras, Wall};
+use bevy::prelude::*;
+use bevy_easings::{EasingChainComponent, EasingComponent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnPhase {
+    Idle,
+    Setup,
+    Deal,
+    Draw,
+    Discard,
+    Claim,
+    Call,
+    NextPlayer,
+    HandEnd,
+}
+
+#[derive(Debug, Default)]
+pub struct HandCursor {
+    index: usize,
+}
+
+pub fn start_hand_system(mut state: ResMut<State<TurnPhase>>) {
+    state.set_next(TurnPhase::Setup).unwrap();
+}
+
+pub fn start_deal_system(mut state: ResMut<State<TurnPhase>>) {
+    state.set_next(TurnPhase::Deal).unwrap();
+}
+
+pub fn wait_for_tiles_system(
+    mut state: ResMut<State<TurnPhase>>,
+    players: Res<Players>,
+    draw_events: Query<Entity, With<DrawTiles>>,
+    call_events: Query<Entity, With<CallTile>>,
+    kan_events: Query<Entity, With<DeclareKan>>,
+    sort_events: Query<Entity, With<SortHand>>,
+    easings: Query<Entity, With<EasingComponent<Transform>>>,
+    easing_chains: Query<Entity, With<EasingChainComponent<Transform>>>,
+) {
+    let is_busy = draw_events.iter().next().is_some()
+        || call_events.iter().next().is_some()
+        || kan_events.iter().next().is_some()
+        || sort_events.iter().next().is_some()
+        || easings.iter().next().is_some()
+        || easing_chains.iter().next().is_some();
+
+    if is_busy {
+        return;
+    }
+
+    let next = match state.current() {
+        TurnPhase::Deal => TurnPhase::Draw,
+        TurnPhase::Draw => TurnPhase::Discard,
+        TurnPhase::Call if players.current_player().needs_replacement() => TurnPhase::Draw,
+        TurnPhase::Call => TurnPhase::Discard,
+        _ => return,
+    };
+
+    state.set_next(next).unwrap();
+}
+
+pub fn draw_system(commands: &mut Commands, players: Res<Players>) {
+    let player = players.current_player();
+    let ident = PlayerIdent::Seat(player.seat);
+
+    if player.needs_replacement() {
+        commands.spawn((DrawTiles::replacement(ident),));
+    } else {
+        commands.spawn((DrawTiles::new(ident, 1),));
+    }
+}
+
+pub fn start_discard_system(
+    commands: &mut Commands,
+    players: Res<Players>,
+    mut cursor: ResMut<HandCursor>,
+    transform_query: Query<&Transform>,
+) {
+    let player = players.current_player();
+
+    if player.r#type != PlayerType::Human {
+        return;
+    }
+
+    cursor.index = player.tiles.len() - 1;
+
+    let tile_entity = player.tiles[cursor.index];
+    if let Ok(transform) = transform_query.get(tile_entity.entity) {
+        let animation = calculate_hand_animation(
+            player.hand_position(cursor.index),
+            player.seat,
+            *transform,
+            true,
+        );
+        commands.insert_one(tile_entity.entity, animation);
+    }
+}
+
+pub fn discard_system(
+    commands: &mut Commands,
+    mut state: ResMut<State<TurnPhase>>,
+    players: Res<Players>,
+    round: Res<Round>,
+    doras: Res<Doras>,
+    wall: Res<Wall>,
+    rules: Res<Rules>,
+    mut cursor: ResMut<HandCursor>,
+    keyboard: Res<Input<KeyCode>>,
+    transform_query: Query<&Transform>,
+) {
+    let player = players.current_player();
+
+    let wants_tsumo = match player.r#type {
+        PlayerType::Ai => true,
+        PlayerType::Human => keyboard.just_pressed(KeyCode::T),
+    };
+
+    if wants_tsumo
+        && player.has_drawn()
+        && round::evaluate_win(player, &players, &wall, &round, &doras, &rules, None).is_some()
+    {
+        commands.spawn((DeclareWin::tsumo(player.seat),));
+        state.set_next(TurnPhase::HandEnd).unwrap();
+        return;
+    }
+
+    let wants_nine_terminals = match player.r#type {
+        PlayerType::Ai => player.ai_wants_nine_terminals(),
+        PlayerType::Human => keyboard.just_pressed(KeyCode::Key9),
+    };
+
+    if wants_nine_terminals
+        && rules.abortive_draws.allows(AbortiveDrawKind::NineTerminals)
+        && ryuukyoku::can_declare_nine_terminals(player, &players)
+    {
+        commands.spawn((AbortiveDraw::new(AbortiveDrawKind::NineTerminals),));
+        state.set_next(TurnPhase::HandEnd).unwrap();
+        return;
+    }
+
+    // No kan can be declared on the last tile of the wall.
+    if players.can_declare_kan() && !wall.is_exhausted() {
+        let kan = match player.r#type {
+            PlayerType::Ai => {
+                let chankan_risk = players
+                    .iter()
+                    .any(|other| other.seat != player.seat && other.riichi.is_some());
+                player.ai_kan(chankan_risk)
+            }
+            PlayerType::Human if keyboard.just_pressed(KeyCode::K) => {
+                player.kan_options().first().copied()
+            }
+            PlayerType::Human => None,
+        };
+
+        if let Some((kind, tile)) = kan {
+            commands.spawn((DeclareKan::new(player.seat, kind, tile),));
+
+            // The tile added to a pon can be robbed by a ron before the replacement is drawn.
+            match kind {
+                KanKind::Concealed => state.set_next(TurnPhase::Call).unwrap(),
+                KanKind::Added => state.set_next(TurnPhase::Claim).unwrap(),
+            }
+            return;
+        }
+    }
+
+    let can_riichi = wall.remaining() >= MIN_TILES_FOR_RIICHI;
+
+    let (index, riichi) = match player.r#type {
+        PlayerType::Ai => {
+            let index = player.ai_discard(&players.visible_tiles(&doras));
+            (
+                index,
+                can_riichi && player.riichi_discards().contains(&index),
+            )
+        }
+        // After riichi the cursor stays on the drawn tile.
+        PlayerType::Human if player.riichi.is_some() => {
+            if keyboard.just_pressed(KeyCode::Return) || keyboard.just_pressed(KeyCode::Space) {
+                (cursor.index, false)
+            } else {
+                return;
+            }
+        }
+        PlayerType::Human => {
+            let previous = cursor.index;
+
+            if keyboard.just_pressed(KeyCode::Left) {
+                cursor.index = cursor.index.saturating_sub(1);
+            }
+
+            if keyboard.just_pressed(KeyCode::Right) {
+                cursor.index = (cursor.index + 1).min(player.tiles.len() - 1);
+            }
+
+            if previous != cursor.index {
+                for &(index, lifted) in [(previous, false), (cursor.index, true)].iter() {
+                    let tile_entity = player.tiles[index];
+                    if let Ok(transform) = transform_query.get(tile_entity.entity) {
+                        let animation = calculate_hand_animation(
+                            player.hand_position(index),
+                            player.seat,
+                            *transform,
+                            lifted,
+                        );
+                        commands.insert_one(tile_entity.entity, animation);
+                    }
+                }
+            }
+
+            if keyboard.just_pressed(KeyCode::Return) || keyboard.just_pressed(KeyCode::Space) {
+                (cursor.index, false)
+            } else if keyboard.just_pressed(KeyCode::R)
+                && can_riichi
+                && player.riichi_discards().contains(&cursor.index)
+            {
+                (cursor.index, true)
+            } else {
+                return;
+            }
+        }
+    };
+
+    let ident = PlayerIdent::Seat(player.seat);
+
+    if riichi {
+        commands.spawn((DiscardTile::riichi(ident, index),));
+    } else {
+        commands.spawn((DiscardTile::new(ident, index),));
+    }
+    state.set_next(TurnPhase::Claim).unwrap();
+}
+
+pub fn next_player_system(mut state: ResMut<State<TurnPhase>>, mut players: ResMut<Players>) {
+    players.end_turn();
+    state.set_next(TurnPhase::Draw).unwrap();
+}
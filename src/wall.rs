@@ -1,17 +1,18 @@
+use crate::fairness::{ShuffledWall, WallCommitment, WallSeed};
 use crate::player::Players;
+use crate::rng::GameRng;
+use crate::rules::Rules;
 use crate::tiles::{Tile, TileAssetData, Wind};
 use bevy::prelude::*;
 use bevy_easings::{Ease, EaseFunction, EasingChainComponent, EasingType};
-use rand::prelude::SliceRandom;
 use rand::Rng;
 use std::collections::VecDeque;
 
-const STACK_SIZE: usize = 2;
+pub const STACK_SIZE: usize = 2;
 const STACKS_PER_SIDE: usize = 17;
-const TILES_PER_SIDE: usize = STACKS_PER_SIDE * 2;
-const TOTAL_TILES: usize = TILES_PER_SIDE * 4;
+const TILES_PER_SIDE: usize = STACKS_PER_SIDE * STACK_SIZE;
 
-const TILES_IN_DEAD_WALL: usize = 7 * STACK_SIZE;
+pub const TILES_IN_DEAD_WALL: usize = 7 * STACK_SIZE;
 
 pub const HALF_WALL_LENGTH: f32 = (STACKS_PER_SIDE as f32 / 2.0) * TileAssetData::WIDTH;
 
@@ -19,50 +20,125 @@ pub const HALF_WALL_LENGTH: f32 = (STACKS_PER_SIDE as f32 / 2.0) * TileAssetData
 pub struct TileEntity {
     pub tile: Tile,
     pub entity: Entity,
+    /// Red fives count as dora but are a normal five otherwise.
+    pub red: bool,
 }
 
 pub struct Wall {
     living_tiles: VecDeque<TileEntity>,
-    rest: Vec<TileEntity>,
 }
 
 impl Wall {
-    pub fn draw(&mut self, amount: usize) -> Vec<TileEntity> {
-        self.living_tiles.drain(0..amount).collect()
+    pub fn draw(&mut self, amount: usize) -> Result<Vec<TileEntity>, &'static str> {
+        if amount > self.living_tiles.len() {
+            Err("There are not enough tiles left in the wall!")
+        } else {
+            Ok(self.living_tiles.drain(0..amount).collect())
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.living_tiles.is_empty()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.living_tiles.len()
+    }
+
+    /// Keeps the dead wall at 14 tiles after a replacement draw by moving the haitei tile into it.
+    pub fn shift_dead_wall(&mut self) {
+        self.living_tiles.pop_back();
     }
 }
 
 pub struct Kans {
     tiles: Vec<TileEntity>,
+    drawn: usize,
 }
 
 impl Kans {
     fn new(tiles: Vec<TileEntity>) -> Self {
-        Self { tiles }
+        Self { tiles, drawn: 0 }
+    }
+
+    pub fn draw(&mut self) -> Result<TileEntity, &'static str> {
+        match self.tiles.get(self.drawn) {
+            Some(&tile_entity) => {
+                self.drawn += 1;
+                Ok(tile_entity)
+            }
+            None => Err("There are no replacement tiles left!"),
+        }
     }
 }
 
 pub struct RevealDora;
 
+pub struct RevealUraDora;
+
 pub struct Doras {
     tiles: Vec<TileEntity>,
     revealed: usize,
+    /// Indicators of kans which are flipped with the next discard.
+    delayed: usize,
 }
 
 impl Doras {
     fn new(tiles: Vec<TileEntity>) -> Self {
-        Self { revealed: 0, tiles }
+        Self {
+            revealed: 0,
+            delayed: 0,
+            tiles,
+        }
+    }
+
+    /// Number of indicators to flip right now for a new kan. Delayed indicators of earlier
+    /// kans are flipped with the next kan at the latest.
+    pub fn reveal_for_kan(&mut self, immediately: bool) -> usize {
+        let delayed = std::mem::take(&mut self.delayed);
+
+        if immediately {
+            delayed + 1
+        } else {
+            self.delayed = 1;
+            delayed
+        }
+    }
+
+    pub fn take_delayed(&mut self) -> usize {
+        std::mem::take(&mut self.delayed)
     }
 
     fn reveal_dora(&mut self) -> Result<TileEntity, &'static str> {
-        if self.revealed == 4 {
-            Err("Already revealed 4 dora tiles!")
+        if self.revealed == self.tiles.len() / STACK_SIZE {
+            Err("Already revealed all dora tiles!")
         } else {
             self.revealed += 1;
             Ok(self.tiles[(self.revealed - 1) * 2])
         }
     }
 
+    pub fn indicators(&self) -> Vec<Tile> {
+        (0..self.revealed)
+            .map(|index| self.tiles[index * 2].tile)
+            .collect()
+    }
+
+    /// The tiles lying under the revealed indicators.
+    pub fn ura_indicators(&self) -> Vec<Tile> {
+        (0..self.revealed)
+            .map(|index| self.tiles[index * 2 + 1].tile)
+            .collect()
+    }
+
+    pub fn count(&self, tiles: &[Tile]) -> u8 {
+        count_doras(&self.indicators(), tiles)
+    }
+
+    pub fn count_ura(&self, tiles: &[Tile]) -> u8 {
+        count_doras(&self.ura_indicators(), tiles)
+    }
+
     pub fn reveal_system(
         commands: &mut Commands,
         mut doras: ResMut<Doras>,
@@ -87,27 +163,66 @@ impl Doras {
             commands.despawn(event);
         }
     }
+
+    pub fn reveal_ura_system(
+        commands: &mut Commands,
+        doras: Res<Doras>,
+        events: Query<Entity, With<RevealUraDora>>,
+        transform_query: Query<&Transform>,
+    ) {
+        for event in events.iter() {
+            for index in 0..doras.revealed {
+                let tile_entity = doras.tiles[index * 2 + 1];
+
+                match transform_query.get(tile_entity.entity) {
+                    Ok(transform) => {
+                        let flip_animation = calculate_tile_flip_animation(*transform);
+                        commands.insert_one(tile_entity.entity, flip_animation);
+                    }
+                    Err(err) => {
+                        error!("Could not query ura dora tile because of {:?}!", err)
+                    }
+                }
+            }
+
+            commands.despawn(event);
+        }
+    }
+}
+
+fn count_doras(indicators: &[Tile], tiles: &[Tile]) -> u8 {
+    indicators
+        .iter()
+        .map(|indicator| {
+            let dora = indicator.dora_from_indicator();
+            tiles.iter().filter(|&&tile| tile == dora).count() as u8
+        })
+        .sum()
 }
 
 pub fn build_wall_system(
     commands: &mut Commands,
     players: Res<Players>,
+    rules: Res<Rules>,
+    mut rng: ResMut<GameRng>,
     tile_asset_data: Res<TileAssetData>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let mut living_tiles: VecDeque<TileEntity> = {
-        let mut tiles = Tile::new_set(false);
-        tiles.shuffle(&mut rand::thread_rng());
+    let tiles: Vec<TileEntity> = {
+        let seed: WallSeed = rng.gen();
+        let wall = ShuffledWall::new(seed, &rules);
+        commands.insert_resource(WallCommitment::new(seed, &wall));
 
-        let living_offset = calculate_living_tiles_offset(players.dealer().seat);
+        let total_tiles = TILES_PER_SIDE * 4;
+        let living_offset = calculate_living_tiles_offset(players.dealer().seat, wall.dice);
 
-        tiles
+        wall.tiles
             .into_iter()
             .enumerate()
-            .map(|(index, tile)| {
+            .map(|(index, (tile, red))| {
                 let pbr = {
                     let transform = calculate_wall_transform_from_index(
-                        TOTAL_TILES + index + living_offset - TILES_IN_DEAD_WALL,
+                        total_tiles + index + living_offset - TILES_IN_DEAD_WALL,
                     );
 
                     let cover_tile_rotation = {
@@ -116,7 +231,7 @@ pub fn build_wall_system(
                         Transform::from_rotation(quat)
                     };
 
-                    let texture = tile_asset_data.get_texture(tile);
+                    let texture = tile_asset_data.get_texture(tile, red);
                     let material = materials.add(StandardMaterial::from(texture));
 
                     PbrBundle {
@@ -133,27 +248,19 @@ pub fn build_wall_system(
                     .current_entity()
                     .unwrap();
 
-                TileEntity { tile, entity }
+                TileEntity { tile, entity, red }
             })
             .collect()
     };
 
-    let rest = living_tiles.drain(0..1 * STACK_SIZE).rev().collect();
-
-    let doras = {
-        let mut dora_tiles = living_tiles.drain(0..4 * STACK_SIZE).rev().collect();
-        swap_neighbors(&mut dora_tiles);
-        Doras::new(dora_tiles)
-    };
+    let split = SplitWall::new(tiles);
 
-    let kans = {
-        let mut kan_tiles = living_tiles.drain(0..2 * STACK_SIZE).rev().collect();
-        swap_neighbors(&mut kan_tiles);
-        Kans::new(kan_tiles)
+    let doras = Doras::new(split.dora_tiles);
+    let kans = Kans::new(split.kan_tiles);
+    let wall = Wall {
+        living_tiles: split.living_tiles,
     };
 
-    let wall = Wall { living_tiles, rest };
-
     commands.insert_resource(wall);
     commands.insert_resource(doras);
     commands.insert_resource(kans);
@@ -161,7 +268,42 @@ pub fn build_wall_system(
     commands.spawn((RevealDora,));
 }
 
-fn swap_neighbors(v: &mut Vec<TileEntity>) {
+/// The tiles of a shuffled wall split into the dead and the living wall.
+pub struct SplitWall<T> {
+    /// Alternately a dora indicator and the ura indicator under it.
+    pub dora_tiles: Vec<T>,
+    /// Replacement tiles for kans in the order they are drawn.
+    pub kan_tiles: Vec<T>,
+    pub living_tiles: VecDeque<T>,
+}
+
+impl<T> SplitWall<T> {
+    pub fn new(tiles: Vec<T>) -> Self {
+        let mut living_tiles = VecDeque::from(tiles);
+
+        let mut dora_tiles: Vec<T> = living_tiles.drain(0..5 * STACK_SIZE).rev().collect();
+        swap_neighbors(&mut dora_tiles);
+
+        let mut kan_tiles: Vec<T> = living_tiles.drain(0..2 * STACK_SIZE).rev().collect();
+        swap_neighbors(&mut kan_tiles);
+
+        Self {
+            dora_tiles,
+            kan_tiles,
+            living_tiles,
+        }
+    }
+
+    pub fn indicators(&self) -> impl Iterator<Item = &T> {
+        self.dora_tiles.iter().step_by(2)
+    }
+
+    pub fn ura_indicators(&self) -> impl Iterator<Item = &T> {
+        self.dora_tiles.iter().skip(1).step_by(2)
+    }
+}
+
+fn swap_neighbors<T>(v: &mut [T]) {
     for i in (0..v.len()).step_by(2) {
         v.swap(i, i + 1);
     }
@@ -185,9 +327,8 @@ fn calculate_wall_transform_from_index(index: usize) -> Transform {
     rotation * translation
 }
 
-fn calculate_living_tiles_offset(seat: Wind) -> usize {
-    let dice = rand::thread_rng().gen_range(2..=12);
-    info!("Rolled: {:?}!", dice);
+fn calculate_living_tiles_offset(seat: Wind, dice: usize) -> usize {
+    info!("Player sitting {:?} rolled {}!", seat, dice);
 
     let side = match seat {
         Wind::East => 1,
@@ -201,32 +342,39 @@ fn calculate_living_tiles_offset(seat: Wind) -> usize {
     side_offset + dice * STACK_SIZE
 }
 
-fn calculate_tile_flip_animation(transform: Transform) -> EasingChainComponent<Transform> {
-    let transform_middle = Transform {
-        translation: Vec3::new(
-            0.0,
-            -(TileAssetData::DEPTH / 2.0 - TileAssetData::HEIGHT / 2.0),
-            0.0,
-        ),
-        rotation: Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
-        scale: Vec3::one(),
-    };
-
-    let transform_to = Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::PI));
+pub fn calculate_tile_flip_animation(transform: Transform) -> EasingChainComponent<Transform> {
+    let [transform_middle, transform_to] = calculate_tile_flip_transforms(transform);
 
     transform
         .ease_to(
-            transform * transform_middle,
+            transform_middle,
             EaseFunction::CircularOut,
             EasingType::Once {
                 duration: std::time::Duration::from_millis(500),
             },
         )
         .ease_to(
-            transform * transform_to,
+            transform_to,
             EaseFunction::CircularIn,
             EasingType::Once {
                 duration: std::time::Duration::from_millis(500),
             },
         )
 }
+
+/// The tile is lifted up on its edge and then laid down on its other side.
+pub fn calculate_tile_flip_transforms(transform: Transform) -> [Transform; 2] {
+    let transform_middle = Transform {
+        translation: Vec3::new(
+            0.0,
+            -(TileAssetData::DEPTH / 2.0 - TileAssetData::HEIGHT / 2.0),
+            0.0,
+        ),
+        rotation: Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        scale: Vec3::one(),
+    };
+
+    let transform_to = Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::PI));
+
+    [transform * transform_middle, transform * transform_to]
+}